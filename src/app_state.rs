@@ -1,9 +1,11 @@
 use anyhow::{Context, Error, Result, bail};
 use crossterm::{QueueableCommand, cursor, terminal};
 use std::{
-    collections::HashSet,
-    fs::{File, OpenOptions},
-    io::{Read, Seek, StdoutLock, Write},
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    io::{self, StdoutLock, Write},
+    mem,
     path::{MAIN_SEPARATOR_STR, Path},
     process::{Command, Stdio},
     sync::{
@@ -14,17 +16,29 @@ use std::{
 };
 
 use crate::{
+    check_cache::{self, CheckCache},
     clear_terminal,
-    cmd::CmdRunner,
+    cmd::{self, CmdRunner},
+    diff,
     editor::{Editor, EditorJoinHandle},
     embedded::EMBEDDED_FILES,
     exercise::{Exercise, RunnableExercise},
-    info_file::ExerciseInfo,
-    term::{self, CheckProgressVisualizer},
+    info_file::{self, ALL_TRACKS, ExerciseInfo, TrackInfo},
+    notes,
+    state_file::{
+        self, BACKUP_FILE_NAME, ExerciseHistory, ExerciseState, STATE_FILE_NAME, StateFile,
+        unix_timestamp,
+    },
+    term::{self, CheckProgressLines, CheckProgressVisualizer},
 };
 
-const STATE_FILE_NAME: &str = ".rustlings-state.txt";
 const DEFAULT_CHECK_PARALLELISM: usize = 8;
+// The versions of exercise files discarded by resets.
+const RESET_BACKUP_DIR: &str = ".rustlings/reset-backups";
+// Copies of the original community exercise files which are restored by resets.
+const PRISTINE_DIR: &str = ".rustlings/pristine";
+// The Git commit from which the copies in `PRISTINE_DIR` were recorded.
+const PRISTINE_COMMIT_PATH: &str = ".rustlings/pristine/.commit";
 
 #[must_use]
 pub enum ExercisesProgress {
@@ -39,6 +53,17 @@ pub enum ExercisesProgress {
 pub enum StateFileStatus {
     Read,
     NotRead,
+    // The state file was empty or invalid and the state was recovered from the backup.
+    Recovered,
+}
+
+/// The result of checking all exercises.
+pub enum CheckAllOutcome {
+    AllDone,
+    /// The index of the first pending exercise.
+    Pending(usize),
+    /// Cancelled in the watch mode (see `cmd::cancel`). No state was changed.
+    Cancelled,
 }
 
 #[derive(Clone, Copy)]
@@ -52,40 +77,44 @@ pub enum CheckProgress {
 pub struct AppState {
     current_exercise_ind: usize,
     exercises: Vec<Exercise>,
-    // Cache the number of done exercises to avoid iterating over all exercises every time.
+    // Cache the number of done exercises in the track to avoid iterating over all exercises every
+    // time.
     n_done: u32,
+    // The name of the selected track. `None` if all exercises are selected.
+    track: Option<&'static str>,
     final_message: &'static str,
-    state_file: File,
     // Preallocated buffer for reading and writing the state file.
     file_buf: Vec<u8>,
     official_exercises: bool,
+    // Another instance holds the lock of the state file. Changes aren't saved.
+    read_only: bool,
     cmd_runner: CmdRunner,
+    // The number of exercises checked in parallel. `None` for the number of CPUs.
+    check_parallelism: Option<usize>,
+    // Reuse the results of unchanged exercises from the last check. They are saved either way.
+    use_check_cache: bool,
     emit_file_links: bool,
     editor: Option<Editor>,
 }
 
 impl AppState {
+    /// `track` replaces the saved track if it is provided.
     pub fn new(
         exercise_infos: Vec<ExerciseInfo>,
+        tracks: &[TrackInfo],
+        track: Option<&str>,
         final_message: &'static str,
         editor: Option<Editor>,
         vs_code_term: bool,
+        read_only: bool,
     ) -> Result<(Self, StateFileStatus)> {
         let cmd_runner = CmdRunner::build()?;
-        let mut state_file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .truncate(false)
-            .open(STATE_FILE_NAME)
-            .with_context(|| {
-                format!("Failed to open or create the state file {STATE_FILE_NAME}")
-            })?;
-
         let dir_canonical_path = term::canonicalize("exercises");
+        let prerequisites = info_file::prerequisites(&exercise_infos)?;
         let mut exercises = exercise_infos
             .into_iter()
-            .map(|exercise_info| {
+            .zip(prerequisites)
+            .map(|(exercise_info, prerequisites)| {
                 let canonical_path = dir_canonical_path.as_deref().map(|dir_canonical_path| {
                     let mut canonical_path;
                     if let Some(dir) = exercise_info.dir {
@@ -117,72 +146,114 @@ impl AppState {
                     canonical_path,
                     test: exercise_info.test,
                     strict_clippy: exercise_info.strict_clippy,
-                    hint: exercise_info.hint.trim_ascii(),
+                    timeout: exercise_info.timeout,
+                    hints: exercise_info
+                        .hint
+                        .levels()
+                        .iter()
+                        .map(|hint| hint.trim_ascii())
+                        .collect(),
+                    prerequisites,
                     // Updated below.
+                    in_track: true,
                     done: false,
+                    skipped: false,
+                    history: ExerciseHistory::default(),
                 }
             })
             .collect::<Vec<_>>();
 
         let mut current_exercise_ind = 0;
-        let mut n_done = 0;
-        let mut file_buf = Vec::with_capacity(2048);
+        let mut saved_track = None;
+        let mut file_buf = Vec::with_capacity(1 << 14);
+        let mut migrated = false;
         let state_file_status = 'block: {
-            if state_file.read_to_end(&mut file_buf).is_err() {
-                break 'block StateFileStatus::NotRead;
-            }
+            if state_file::read(STATE_FILE_NAME, &mut file_buf) {
+                state_file::check_content_format_version(&file_buf)?;
 
-            // See `Self::write` for more information about the file format.
-            let mut lines = file_buf.split(|c| *c == b'\n').skip(2);
+                if let Some((state, was_migrated)) = StateFile::parse(&file_buf) {
+                    migrated = was_migrated;
+                    saved_track = state.track.as_deref().map(String::from);
+                    current_exercise_ind = apply_state(&mut exercises, state);
 
-            let Some(current_exercise_name) = lines.next() else {
-                break 'block StateFileStatus::NotRead;
-            };
-
-            if current_exercise_name.is_empty() || lines.next().is_none() {
-                break 'block StateFileStatus::NotRead;
+                    break 'block StateFileStatus::Read;
+                }
             }
 
-            let mut done_exercises = HashSet::with_capacity(exercises.len());
-
-            for done_exercise_name in lines {
-                if done_exercise_name.is_empty() {
-                    break;
-                }
-                done_exercises.insert(done_exercise_name);
+            // A missing state file means that Rustlings runs for the first time.
+            if !Path::new(STATE_FILE_NAME).exists() {
+                break 'block StateFileStatus::NotRead;
             }
 
-            for (ind, exercise) in exercises.iter_mut().enumerate() {
-                if done_exercises.contains(exercise.name.as_bytes()) {
-                    exercise.done = true;
-                    n_done += 1;
-                }
+            // The state file is empty or invalid (e.g. edited manually).
+            // Fall back to the previous state.
+            if state_file::read(BACKUP_FILE_NAME, &mut file_buf) {
+                state_file::check_content_format_version(&file_buf)?;
+
+                if let Some((state, was_migrated)) = StateFile::parse(&file_buf) {
+                    // Restore the state file before it is backed up by the next write.
+                    if !read_only {
+                        fs::copy(BACKUP_FILE_NAME, STATE_FILE_NAME).with_context(|| {
+                            format!(
+                                "Failed to restore the state file {STATE_FILE_NAME} from the backup {BACKUP_FILE_NAME}",
+                            )
+                        })?;
+                    }
+                    migrated = was_migrated;
+                    saved_track = state.track.as_deref().map(String::from);
+                    current_exercise_ind = apply_state(&mut exercises, state);
 
-                if exercise.name.as_bytes() == current_exercise_name {
-                    current_exercise_ind = ind;
+                    break 'block StateFileStatus::Recovered;
                 }
             }
 
-            StateFileStatus::Read
+            StateFileStatus::NotRead
         };
 
-        file_buf.clear();
-        file_buf.extend_from_slice(STATE_FILE_HEADER);
-
-        let slf = Self {
+        let track_changed = track.is_some_and(|track| {
+            saved_track.as_deref() != Some(track).filter(|track| *track != ALL_TRACKS)
+        });
+        let track = select_track(
+            &mut exercises,
+            tracks,
+            track.or(saved_track.as_deref()),
+            track.is_some(),
+        )?;
+
+        let mut slf = Self {
             current_exercise_ind,
+            n_done: count_done(&exercises),
             exercises,
-            n_done,
+            track,
             final_message,
-            state_file,
             file_buf,
             official_exercises: !Path::new("info.toml").exists(),
+            read_only,
             cmd_runner,
+            check_parallelism: None,
+            use_check_cache: true,
             // VS Code has its own file link handling
             emit_file_links: !vs_code_term,
             editor,
         };
 
+        // Continue in the selected track.
+        if track_changed && !slf.current_exercise().in_track {
+            slf.current_exercise_ind = slf
+                .first_pending_unlocked(0..slf.exercises.len())
+                .or_else(|| slf.exercises.iter().position(|exercise| exercise.in_track))
+                .unwrap_or(slf.current_exercise_ind);
+        }
+
+        // Replace the old format right away.
+        if migrated || track_changed {
+            slf.write()?;
+        }
+
+        if !slf.official_exercises && !read_only {
+            slf.record_pristine_exercises()?;
+        }
+
         Ok((slf, state_file_status))
     }
 
@@ -194,12 +265,27 @@ impl AppState {
         &self.exercises
     }
 
+    /// The number of done exercises in the selected track.
     pub fn n_done(&self) -> u32 {
         self.n_done
     }
 
+    /// The number of pending exercises in the selected track.
     pub fn n_pending(&self) -> u32 {
-        self.exercises.len() as u32 - self.n_done
+        self.n_exercises() - self.n_done
+    }
+
+    /// The number of exercises in the selected track.
+    pub fn n_exercises(&self) -> u32 {
+        self.exercises
+            .iter()
+            .filter(|exercise| exercise.in_track)
+            .count() as u32
+    }
+
+    /// The name of the selected track. `None` if all exercises are selected.
+    pub fn track(&self) -> Option<&'static str> {
+        self.track
     }
 
     pub fn current_exercise(&self) -> &Exercise {
@@ -210,43 +296,94 @@ impl AppState {
         &self.cmd_runner
     }
 
+    pub fn cmd_runner_mut(&mut self) -> &mut CmdRunner {
+        &mut self.cmd_runner
+    }
+
+    /// Set the number of exercises checked in parallel. `0` uses the number of CPUs.
+    pub fn set_check_parallelism(&mut self, n_threads: usize) {
+        self.check_parallelism = (n_threads > 0).then_some(n_threads);
+    }
+
+    /// Check all exercises instead of only the ones which changed since their last check.
+    pub fn disable_check_cache(&mut self) {
+        self.use_check_cache = false;
+    }
+
     pub fn emit_file_links(&self) -> bool {
         self.emit_file_links
     }
 
-    // Write the state file.
-    // The file's format is very simple:
-    // - The first line is a comment.
-    // - The second line is an empty line.
-    // - The third line is the name of the current exercise. It must end with `\n` even if there
-    // are no done exercises.
-    // - The fourth line is an empty line.
-    // - All remaining lines are the names of done exercises.
+    /// The state as it is written to the state file.
+    pub fn state(&self) -> StateFile<'_> {
+        let exercise_states = self
+            .exercises
+            .iter()
+            .map(|exercise| ExerciseState {
+                name: Cow::Borrowed(exercise.name),
+                done: exercise.done,
+                skipped: exercise.skipped,
+                history: exercise.history.clone(),
+            })
+            .collect();
+
+        let mut state = StateFile::new(self.current_exercise().name, exercise_states);
+        state.track = self.track.map(Cow::Borrowed);
+
+        state
+    }
+
+    /// Replace the whole state (e.g. by an imported one) and save.
+    /// Exercises which aren't contained in the state are pending without history.
+    /// The selected track is kept.
+    pub fn replace_state(&mut self, state: StateFile) -> Result<()> {
+        self.current_exercise_ind = apply_state(&mut self.exercises, state);
+        self.n_done = count_done(&self.exercises);
+
+        self.write()
+    }
+
+    // Write the state file. See `StateFile` for its format.
     fn write(&mut self) -> Result<()> {
-        self.file_buf.truncate(STATE_FILE_HEADER.len());
+        if self.read_only {
+            return Ok(());
+        }
 
-        self.file_buf
-            .extend_from_slice(self.current_exercise().name.as_bytes());
+        // Taken to serialize the state which borrows `self`.
+        let mut file_buf = mem::take(&mut self.file_buf);
+        file_buf.clear();
+        let res = serde_json::ser::to_writer_pretty(&mut file_buf, &self.state());
+        self.file_buf = file_buf;
+        res.context("Failed to serialize the state")?;
         self.file_buf.push(b'\n');
 
-        for exercise in &self.exercises {
-            if exercise.done {
-                self.file_buf.push(b'\n');
-                self.file_buf.extend_from_slice(exercise.name.as_bytes());
-            }
+        state_file::save(&self.file_buf)
+    }
+
+    /// Record a run of the current exercise by the user and save.
+    pub fn record_run(&mut self) -> Result<()> {
+        let history = &mut self.exercises[self.current_exercise_ind].history;
+        history.runs += 1;
+        history.started_at.get_or_insert_with(unix_timestamp);
+
+        self.write()
+    }
+
+    /// Record that a hint level (starting at 1) of the current exercise was shown and save.
+    pub fn reveal_hint(&mut self, level: u32) -> Result<()> {
+        let exercise = &mut self.exercises[self.current_exercise_ind];
+        let n_levels = exercise.n_hint_levels();
+        if level == 0 || level > n_levels {
+            bail!(
+                "The hint level {level} doesn't exist. The exercise `{}` has hint levels from 1 to {n_levels}",
+                exercise.name,
+            );
         }
 
-        self.state_file
-            .rewind()
-            .with_context(|| format!("Failed to rewind the state file {STATE_FILE_NAME}"))?;
-        self.state_file
-            .set_len(0)
-            .with_context(|| format!("Failed to truncate the state file {STATE_FILE_NAME}"))?;
-        self.state_file
-            .write_all(&self.file_buf)
-            .with_context(|| format!("Failed to write the state file {STATE_FILE_NAME}"))?;
+        exercise.history.hints_viewed += 1;
+        exercise.history.hint_level = exercise.history.hint_level.max(level);
 
-        Ok(())
+        self.write()
     }
 
     pub fn set_current_exercise_ind(&mut self, exercise_ind: usize) -> Result<()> {
@@ -289,9 +426,18 @@ impl AppState {
 
         exercise.done = done;
         if done {
-            self.n_done += 1;
-        } else {
-            self.n_done -= 1;
+            exercise
+                .history
+                .finished_at
+                .get_or_insert_with(unix_timestamp);
+            exercise.skipped = false;
+        }
+        if exercise.in_track {
+            if done {
+                self.n_done += 1;
+            } else {
+                self.n_done -= 1;
+            }
         }
 
         Ok(true)
@@ -306,72 +452,256 @@ impl AppState {
         Ok(())
     }
 
-    // Official exercises: Dump the original file from the binary.
-    // Community exercises: Reset the exercise file with `git stash`.
-    fn reset(&self, exercise_ind: usize, path: &str) -> Result<()> {
+    // Record a copy of every community exercise file which doesn't have one yet to be able to reset it.
+    // The file in the last Git commit is preferred over the current one which might already be
+    // modified. Git is optional though.
+    // All copies are recorded again when the last commit changes (e.g. after pulling updated
+    // exercises).
+    fn record_pristine_exercises(&self) -> Result<()> {
+        let head_commit = git_head_commit();
+        let head_changed = head_commit.as_ref().is_some_and(|head_commit| {
+            fs::read(PRISTINE_COMMIT_PATH).ok().as_ref() != Some(head_commit)
+        });
+
+        for exercise in &self.exercises {
+            let pristine_path = pristine_path(exercise.path);
+            let recorded = Path::new(&pristine_path).exists();
+            if recorded && !head_changed {
+                continue;
+            }
+
+            let content = match git_show_head(exercise.path) {
+                Some(v) => v,
+                // Keep the copy of a file which isn't committed.
+                None if recorded => continue,
+                None => match fs::read(exercise.path) {
+                    Ok(v) => v,
+                    // Recorded on a later run if the file is restored.
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(e) => {
+                        return Err(e).with_context(|| {
+                            format!("Failed to read the exercise file {}", exercise.path)
+                        });
+                    }
+                },
+            };
+
+            if let Some(dir) = Path::new(&pristine_path).parent() {
+                fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create the directory {}", dir.display()))?;
+            }
+            fs::write(&pristine_path, content)
+                .with_context(|| format!("Failed to write the file {pristine_path}"))?;
+        }
+
+        if head_changed && let Some(head_commit) = head_commit {
+            fs::create_dir_all(PRISTINE_DIR)
+                .with_context(|| format!("Failed to create the directory {PRISTINE_DIR}"))?;
+            fs::write(PRISTINE_COMMIT_PATH, head_commit)
+                .with_context(|| format!("Failed to write the file {PRISTINE_COMMIT_PATH}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// The original content of an exercise file.
+    /// Official exercises: The embedded file.
+    /// Community exercises: The copy recorded by `record_pristine_exercises`.
+    pub fn original_exercise(&self, exercise_ind: usize) -> Result<Cow<'static, [u8]>> {
         if self.official_exercises {
-            return EMBEDDED_FILES
-                .write_exercise_to_disk(exercise_ind, path)
-                .with_context(|| format!("Failed to reset the exercise {path}"));
+            return Ok(Cow::Borrowed(EMBEDDED_FILES.exercise_content(exercise_ind)));
         }
 
-        let output = Command::new("git")
-            .arg("stash")
-            .arg("push")
-            .arg("--")
-            .arg(path)
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .output()
-            .with_context(|| format!("Failed to run `git stash push -- {path}`"))?;
+        let pristine_path = pristine_path(self.exercises[exercise_ind].path);
+        match fs::read(&pristine_path) {
+            Ok(v) => Ok(Cow::Owned(v)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => bail!(
+                "The original version of the exercise {} wasn't recorded in {PRISTINE_DIR}",
+                self.exercises[exercise_ind].path,
+            ),
+            Err(e) => Err(e).with_context(|| format!("Failed to read the file {pristine_path}")),
+        }
+    }
 
-        if !output.status.success() {
-            bail!(
-                "`git stash push -- {path}` didn't run successfully: {}",
-                String::from_utf8_lossy(&output.stderr),
-            );
+    /// Write a diff of the changes to an exercise file compared to its original version.
+    /// Returns `false` if there are no changes.
+    pub fn write_exercise_changes(
+        &self,
+        exercise_ind: usize,
+        writer: &mut impl Write,
+    ) -> Result<bool> {
+        let exercise = self.exercises.get(exercise_ind).context(BAD_INDEX_ERR)?;
+        let original = self.original_exercise(exercise_ind)?;
+        let current = fs::read(exercise.path)
+            .with_context(|| format!("Failed to read the exercise file {}", exercise.path))?;
+
+        diff::write_diff(
+            writer,
+            &format!("{} (original)", exercise.path),
+            &String::from_utf8_lossy(&original),
+            &format!("{} (yours)", exercise.path),
+            &String::from_utf8_lossy(&current),
+            false,
+        )
+        .context("Failed to write the diff")
+    }
+
+    // Keep a changed exercise file before resetting it to be able to undo the reset.
+    // Returns `false` if the file wasn't changed.
+    fn backup_before_reset(&self, exercise_ind: usize) -> Result<bool> {
+        let exercise = &self.exercises[exercise_ind];
+        let content = match fs::read(exercise.path) {
+            Ok(v) => v,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to read the exercise file {}", exercise.path)
+                });
+            }
+        };
+
+        // Don't replace the backup of an earlier reset with the original file.
+        if self
+            .original_exercise(exercise_ind)
+            .is_ok_and(|original| *original == content)
+        {
+            return Ok(false);
         }
 
-        Ok(())
+        fs::create_dir_all(RESET_BACKUP_DIR)
+            .with_context(|| format!("Failed to create the directory {RESET_BACKUP_DIR}"))?;
+        let backup_path = reset_backup_path(exercise.name);
+        fs::write(&backup_path, content)
+            .with_context(|| format!("Failed to write the backup file {backup_path}"))?;
+
+        Ok(true)
+    }
+
+    // Official exercises: Dump the original file from the binary.
+    // Community exercises: Restore the recorded copy of the original file.
+    // Returns `true` if the file was changed and can be restored with `undo_reset_current_exercise`.
+    fn reset(&self, exercise_ind: usize, path: &str) -> Result<bool> {
+        let changed = self.backup_before_reset(exercise_ind)?;
+
+        if self.official_exercises {
+            EMBEDDED_FILES
+                .write_exercise_to_disk(exercise_ind, path)
+                .with_context(|| format!("Failed to reset the exercise {path}"))?;
+
+            return Ok(changed);
+        }
+
+        let original = self.original_exercise(exercise_ind)?;
+        fs::write(path, original)
+            .with_context(|| format!("Failed to reset the exercise {path}"))?;
+
+        Ok(changed)
     }
 
-    pub fn reset_current_exercise(&mut self) -> Result<()> {
+    // Returns `true` if the file was changed and can be restored.
+    pub fn reset_current_exercise(&mut self) -> Result<bool> {
         self.set_pending(self.current_exercise_ind)?;
         let exercise = self.current_exercise();
         self.reset(self.current_exercise_ind, exercise.path)
     }
 
-    // Reset the exercise by index and return its name.
-    pub fn reset_exercise_by_ind(&mut self, exercise_ind: usize) -> Result<&'static str> {
+    // Reset the exercise by index and return its name and whether the file was changed.
+    pub fn reset_exercise_by_ind(&mut self, exercise_ind: usize) -> Result<(&'static str, bool)> {
         if exercise_ind >= self.exercises.len() {
             bail!(BAD_INDEX_ERR);
         }
 
         self.set_pending(exercise_ind)?;
         let exercise = &self.exercises[exercise_ind];
-        self.reset(exercise_ind, exercise.path)?;
+        let changed = self.reset(exercise_ind, exercise.path)?;
+
+        Ok((exercise.name, changed))
+    }
+
+    /// Restore the version of the current exercise file which was discarded by its last reset.
+    pub fn undo_reset_current_exercise(&mut self) -> Result<()> {
+        let exercise = self.current_exercise();
+        let backup_path = reset_backup_path(exercise.name);
+        let content = match fs::read(&backup_path) {
+            Ok(v) => v,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                bail!(
+                    "There are no changes to the exercise `{}` discarded by a reset",
+                    exercise.name,
+                );
+            }
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read the backup file {backup_path}"));
+            }
+        };
+
+        fs::write(exercise.path, content)
+            .with_context(|| format!("Failed to write the exercise file {}", exercise.path))?;
+        fs::remove_file(&backup_path)
+            .with_context(|| format!("Failed to remove the backup file {backup_path}"))
+    }
 
-        Ok(exercise.name)
+    /// Mark the current exercise as skipped and move on to the next pending exercise.
+    /// Skipped exercises are revisited after all other pending exercises.
+    /// Returns `false` if there is no other pending exercise to move on to.
+    pub fn skip_current_exercise(&mut self) -> Result<bool> {
+        let exercise = &mut self.exercises[self.current_exercise_ind];
+        if exercise.done {
+            bail!("The exercise `{}` is already done", exercise.name);
+        }
+        exercise.skipped = true;
+
+        let moved_on = if let Some(ind) = self.next_pending_exercise_ind() {
+            self.current_exercise_ind = ind;
+            true
+        } else {
+            false
+        };
+        self.write()?;
+
+        Ok(moved_on)
+    }
+
+    /// Whether some prerequisites of the exercise aren't done yet.
+    pub fn is_locked(&self, exercise_ind: usize) -> bool {
+        self.exercises[exercise_ind]
+            .prerequisites
+            .iter()
+            .any(|&ind| !self.exercises[ind].done)
+    }
+
+    // Return the first pending exercise of the track which isn't locked or skipped among the
+    // indices. Fall back to the first locked pending exercise (e.g. because of a cycle) and then to
+    // the first skipped one.
+    fn first_pending_unlocked(&self, inds: impl Iterator<Item = usize>) -> Option<usize> {
+        let mut first_locked = None;
+        let mut first_skipped = None;
+        for ind in inds {
+            let exercise = &self.exercises[ind];
+            if exercise.done || !exercise.in_track {
+                continue;
+            }
+
+            if exercise.skipped {
+                first_skipped.get_or_insert(ind);
+            } else if self.is_locked(ind) {
+                first_locked.get_or_insert(ind);
+            } else {
+                return Some(ind);
+            }
+        }
+
+        first_locked.or(first_skipped)
     }
 
     // Return the index of the next pending exercise or `None` if all exercises are done.
     fn next_pending_exercise_ind(&self) -> Option<usize> {
-        let next_ind = self.current_exercise_ind + 1;
-        self.exercises
-            // If the exercise done isn't the last, search for pending exercises after it.
-            .get(next_ind..)
-            .and_then(|later_exercises| {
-                later_exercises
-                    .iter()
-                    .position(|exercise| !exercise.done)
-                    .map(|ind| next_ind + ind)
-            })
-            // Search from the start.
-            .or_else(|| {
-                self.exercises[..self.current_exercise_ind]
-                    .iter()
-                    .position(|exercise| !exercise.done)
-            })
+        // Search for pending exercises after the current one first. Then from the start.
+        self.first_pending_unlocked(
+            (self.current_exercise_ind + 1..self.exercises.len())
+                .chain(0..self.current_exercise_ind),
+        )
     }
 
     /// Official exercises: Dump the solution file from the binary and return its path.
@@ -398,49 +728,96 @@ impl AppState {
         }
     }
 
-    fn check_all_exercises_impl(&mut self, stdout: &mut StdoutLock) -> Result<Option<usize>> {
-        let term_width = terminal::size()
-            .context("Failed to get the terminal size")?
-            .0;
-        let mut progress_visualizer = CheckProgressVisualizer::build(stdout, term_width)?;
+    // `on_progress` is called with the progress of all exercises whenever one of them changes.
+    fn check_all_exercises_impl(
+        &mut self,
+        mut on_progress: impl FnMut(&[CheckProgress]) -> io::Result<()>,
+    ) -> Result<CheckAllOutcome> {
+        // Exercises outside of the selected track aren't checked.
+        let exercise_inds = self
+            .exercises
+            .iter()
+            .enumerate()
+            .filter(|(_, exercise)| exercise.in_track)
+            .map(|(exercise_ind, _)| exercise_ind)
+            .collect::<Vec<_>>();
+
+        let mut check_cache = CheckCache::load();
+        let hashes = exercise_inds
+            .iter()
+            .map(|&exercise_ind| {
+                check_cache.as_ref().and_then(|_| {
+                    check_cache::exercise_hash(&self.exercises[exercise_ind], &self.cmd_runner)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // The progresses of the exercises in `exercise_inds`.
+        let mut progresses = vec![CheckProgress::None; exercise_inds.len()];
+        if self.use_check_cache
+            && let Some(check_cache) = &check_cache
+        {
+            for ((progress, &exercise_ind), hash) in
+                progresses.iter_mut().zip(&exercise_inds).zip(&hashes)
+            {
+                if let Some(hash) = *hash
+                    && let Some(done) = check_cache.done(self.exercises[exercise_ind].name, hash)
+                {
+                    *progress = if done {
+                        CheckProgress::Done
+                    } else {
+                        CheckProgress::Pending
+                    };
+                }
+            }
+        }
+        on_progress(&progresses)?;
 
-        let next_exercise_ind = AtomicUsize::new(0);
-        let mut progresses = vec![CheckProgress::None; self.exercises.len()];
+        let progress_inds_to_check = progresses
+            .iter()
+            .enumerate()
+            .filter(|(_, progress)| matches!(progress, CheckProgress::None))
+            .map(|(progress_ind, _)| progress_ind)
+            .collect::<Vec<_>>();
+        let next_ind = AtomicUsize::new(0);
 
         thread::scope(|s| {
             let (exercise_progress_sender, exercise_progress_receiver) = mpsc::channel();
-            let n_threads = thread::available_parallelism()
-                .map_or(DEFAULT_CHECK_PARALLELISM, |count| count.get());
+            let n_threads = self.check_parallelism.unwrap_or_else(|| {
+                thread::available_parallelism()
+                    .map_or(DEFAULT_CHECK_PARALLELISM, |count| count.get())
+            });
 
             for _ in 0..n_threads {
                 let exercise_progress_sender = exercise_progress_sender.clone();
-                let next_exercise_ind = &next_exercise_ind;
+                let exercise_inds = &exercise_inds;
+                let progress_inds_to_check = &progress_inds_to_check;
+                let next_ind = &next_ind;
                 let slf = &self;
                 thread::Builder::new()
                     .spawn_scoped(s, move || {
-                        loop {
-                            let exercise_ind = next_exercise_ind.fetch_add(1, Relaxed);
-                            let Some(exercise) = slf.exercises.get(exercise_ind) else {
-                                // No more exercises.
-                                break;
-                            };
+                        // Until there are no more exercises.
+                        while let Some(&progress_ind) =
+                            progress_inds_to_check.get(next_ind.fetch_add(1, Relaxed))
+                        {
+                            let exercise = &slf.exercises[exercise_inds[progress_ind]];
 
                             if exercise_progress_sender
-                                .send((exercise_ind, CheckProgress::Checking))
+                                .send((progress_ind, CheckProgress::Checking))
                                 .is_err()
                             {
                                 break;
                             }
 
-                            let success = exercise.run_exercise(None, &slf.cmd_runner);
-                            let progress = match success {
-                                Ok(true) => CheckProgress::Done,
-                                Ok(false) => CheckProgress::Pending,
+                            let outcome = exercise.run_exercise(None, &slf.cmd_runner);
+                            let progress = match outcome {
+                                Ok(outcome) if outcome.success() => CheckProgress::Done,
+                                Ok(_) => CheckProgress::Pending,
                                 Err(_) => CheckProgress::None,
                             };
 
                             if exercise_progress_sender
-                                .send((exercise_ind, progress))
+                                .send((progress_ind, progress))
                                 .is_err()
                             {
                                 break;
@@ -453,17 +830,22 @@ impl AppState {
             // Drop this sender to detect when the last thread is done.
             drop(exercise_progress_sender);
 
-            while let Ok((exercise_ind, progress)) = exercise_progress_receiver.recv() {
-                progresses[exercise_ind] = progress;
-                progress_visualizer.update(&progresses)?;
+            while let Ok((progress_ind, progress)) = exercise_progress_receiver.recv() {
+                progresses[progress_ind] = progress;
+                on_progress(&progresses)?;
             }
 
             Ok::<_, Error>(())
         })?;
 
+        // Cancelled in the watch mode. Leave the exercises' states untouched.
+        if cmd::cancelled() {
+            return Ok(CheckAllOutcome::Cancelled);
+        }
+
         let mut first_pending_exercise_ind = None;
-        for exercise_ind in 0..progresses.len() {
-            match progresses[exercise_ind] {
+        for (progress_ind, &exercise_ind) in exercise_inds.iter().enumerate() {
+            match progresses[progress_ind] {
                 CheckProgress::Done => {
                     self.set_status(exercise_ind, true)?;
                 }
@@ -477,72 +859,135 @@ impl AppState {
                     // If we got an error while checking all exercises in parallel,
                     // it could be because we exceeded the limit of open file descriptors.
                     // Therefore, try running exercises with errors sequentially.
-                    progresses[exercise_ind] = CheckProgress::Checking;
-                    progress_visualizer.update(&progresses)?;
+                    progresses[progress_ind] = CheckProgress::Checking;
+                    on_progress(&progresses)?;
 
                     let exercise = &self.exercises[exercise_ind];
-                    let success = exercise.run_exercise(None, &self.cmd_runner)?;
+                    let success = exercise.run_exercise(None, &self.cmd_runner)?.success();
                     if success {
-                        progresses[exercise_ind] = CheckProgress::Done;
+                        progresses[progress_ind] = CheckProgress::Done;
                     } else {
-                        progresses[exercise_ind] = CheckProgress::Pending;
+                        progresses[progress_ind] = CheckProgress::Pending;
                         if first_pending_exercise_ind.is_none() {
                             first_pending_exercise_ind = Some(exercise_ind);
                         }
                     }
                     self.set_status(exercise_ind, success)?;
-                    progress_visualizer.update(&progresses)?;
+                    on_progress(&progresses)?;
+                }
+            }
+        }
+
+        if let Some(check_cache) = &mut check_cache
+            && !self.read_only
+        {
+            for ((&exercise_ind, hash), progress) in
+                exercise_inds.iter().zip(&hashes).zip(&progresses)
+            {
+                if let Some(hash) = *hash {
+                    check_cache.insert(
+                        self.exercises[exercise_ind].name,
+                        hash,
+                        matches!(progress, CheckProgress::Done),
+                    );
                 }
             }
+            check_cache.save()?;
         }
 
         self.write()?;
 
-        Ok(first_pending_exercise_ind)
+        Ok(
+            match first_pending_exercise_ind
+                .and_then(|ind| self.first_pending_unlocked(ind..self.exercises.len()))
+            {
+                Some(ind) => CheckAllOutcome::Pending(ind),
+                None => CheckAllOutcome::AllDone,
+            },
+        )
     }
 
-    // Return the exercise index of the first pending exercise found.
-    pub fn check_all_exercises(&mut self, stdout: &mut StdoutLock) -> Result<Option<usize>> {
+    // Check all exercises while showing the progress in the terminal.
+    pub fn check_all_exercises(&mut self, stdout: &mut StdoutLock) -> Result<CheckAllOutcome> {
+        let term_width = terminal::size()
+            .context("Failed to get the terminal size")?
+            .0;
+
         stdout.queue(cursor::Hide)?;
-        let res = self.check_all_exercises_impl(stdout);
+        let res = CheckProgressVisualizer::build(stdout, term_width)
+            .map_err(Error::from)
+            .and_then(|mut progress_visualizer| {
+                self.check_all_exercises_impl(|progresses| progress_visualizer.update(progresses))
+            });
         stdout.queue(cursor::Show)?;
 
         res
     }
 
+    // Like `check_all_exercises` but without showing the progress in the terminal.
+    pub fn check_all_exercises_headless(&mut self) -> Result<CheckAllOutcome> {
+        self.check_all_exercises_impl(|_| Ok(()))
+    }
+
+    // Like `check_all_exercises` but with one line per exercise instead of terminal features.
+    pub fn check_all_exercises_lines(
+        &mut self,
+        stdout: &mut StdoutLock,
+    ) -> Result<CheckAllOutcome> {
+        let exercise_names = self
+            .exercises
+            .iter()
+            .filter(|exercise| exercise.in_track)
+            .map(|exercise| exercise.name)
+            .collect();
+        let mut progress_lines = CheckProgressLines::new(stdout, exercise_names);
+        self.check_all_exercises_impl(|progresses| progress_lines.update(progresses))
+    }
+
     /// Mark the current exercise as done and move on to the next pending exercise if one exists.
     /// If all exercises are marked as done, run all of them to make sure that they are actually
     /// done. If an exercise which is marked as done fails, mark it as pending and continue on it.
+    /// Without `stdout`, nothing is printed (neither the check progress nor the final message).
     pub fn done_current_exercise<const CLEAR_BEFORE_FINAL_CHECK: bool>(
         &mut self,
-        stdout: &mut StdoutLock,
+        stdout: Option<&mut StdoutLock>,
     ) -> Result<ExercisesProgress> {
-        let exercise = &mut self.exercises[self.current_exercise_ind];
-        if !exercise.done {
-            exercise.done = true;
-            self.n_done += 1;
-        }
+        self.set_status(self.current_exercise_ind, true)?;
 
         if let Some(ind) = self.next_pending_exercise_ind() {
             self.set_current_exercise_ind(ind)?;
             return Ok(ExercisesProgress::NewPending);
         }
 
+        let Some(stdout) = stdout else {
+            return match self.check_all_exercises_headless()? {
+                CheckAllOutcome::AllDone => Ok(ExercisesProgress::AllDone),
+                CheckAllOutcome::Pending(first_pending_exercise_ind) => {
+                    self.set_current_exercise_ind(first_pending_exercise_ind)?;
+                    Ok(ExercisesProgress::NewPending)
+                }
+                CheckAllOutcome::Cancelled => Ok(ExercisesProgress::CurrentPending),
+            };
+        };
+
         if CLEAR_BEFORE_FINAL_CHECK {
             clear_terminal(stdout)?;
         } else {
             stdout.write_all(b"\n")?;
         }
 
-        if let Some(first_pending_exercise_ind) = self.check_all_exercises(stdout)? {
-            self.set_current_exercise_ind(first_pending_exercise_ind)?;
-
-            return Ok(ExercisesProgress::NewPending);
+        match self.check_all_exercises(stdout)? {
+            CheckAllOutcome::AllDone => {
+                self.render_final_message(stdout)?;
+                Ok(ExercisesProgress::AllDone)
+            }
+            CheckAllOutcome::Pending(first_pending_exercise_ind) => {
+                self.set_current_exercise_ind(first_pending_exercise_ind)?;
+                Ok(ExercisesProgress::NewPending)
+            }
+            // The current exercise stays done. The check can be started again.
+            CheckAllOutcome::Cancelled => Ok(ExercisesProgress::CurrentPending),
         }
-
-        self.render_final_message(stdout)?;
-
-        Ok(ExercisesProgress::AllDone)
     }
 
     pub fn render_final_message(&self, stdout: &mut StdoutLock) -> Result<()> {
@@ -560,12 +1005,26 @@ impl AppState {
 
     pub fn open_editor(&mut self) -> Result<EditorJoinHandle> {
         if let Some(editor) = self.editor.take() {
-            return editor.open(self.current_exercise_ind, self.current_exercise().path);
+            return editor.open(Cow::Borrowed(self.current_exercise().path));
         }
 
         Ok(EditorJoinHandle::default())
     }
 
+    /// Open the notes of the current exercise in the editor after creating the notes file if it
+    /// doesn't exist yet. Returns `false` if there is no editor to open it in.
+    pub fn open_notes(&mut self) -> Result<bool> {
+        let path = notes::create(self.current_exercise().name)?;
+        let Some(editor) = self.editor.take() else {
+            return Ok(false);
+        };
+
+        let handle = editor.open(Cow::Owned(path))?;
+        self.join_editor_handle(handle)?;
+
+        Ok(true)
+    }
+
     pub fn join_editor_handle(&mut self, handle: EditorJoinHandle) -> Result<()> {
         self.editor = handle.join()?;
 
@@ -581,8 +1040,122 @@ impl AppState {
     }
 }
 
+fn reset_backup_path(exercise_name: &str) -> String {
+    format!("{RESET_BACKUP_DIR}/{exercise_name}.rs")
+}
+
+// Mirrors the exercise path like `exercises/…/….rs`.
+fn pristine_path(exercise_path: &str) -> String {
+    format!("{PRISTINE_DIR}/{exercise_path}")
+}
+
+// The hash of the last Git commit.
+// `None` if Git isn't installed, the directory isn't a Git repository or there is no commit yet.
+fn git_head_commit() -> Option<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("rev-parse")
+        .arg("HEAD")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    output.status.success().then_some(output.stdout)
+}
+
+// The content of a file in the last Git commit.
+// `None` if Git isn't installed, the directory isn't a Git repository or the file isn't committed.
+fn git_show_head(path: &str) -> Option<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!("HEAD:./{path}"))
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    output.status.success().then_some(output.stdout)
+}
+
+// Set the status and history of the exercises from the state.
+// Returns the index of the current exercise.
+fn apply_state(exercises: &mut [Exercise], state: StateFile) -> usize {
+    let mut exercise_states = state
+        .exercises
+        .into_iter()
+        .map(
+            |ExerciseState {
+                 name,
+                 done,
+                 skipped,
+                 history,
+             }| (name, (done, skipped, history)),
+        )
+        .collect::<HashMap<_, _>>();
+
+    let mut current_exercise_ind = 0;
+    for (ind, exercise) in exercises.iter_mut().enumerate() {
+        (exercise.done, exercise.skipped, exercise.history) =
+            exercise_states.remove(exercise.name).unwrap_or_default();
+
+        if exercise.name == state.current_exercise {
+            current_exercise_ind = ind;
+        }
+    }
+
+    current_exercise_ind
+}
+
+// The number of done exercises in the selected track.
+fn count_done(exercises: &[Exercise]) -> u32 {
+    exercises
+        .iter()
+        .filter(|exercise| exercise.done && exercise.in_track)
+        .count() as u32
+}
+
+// Mark the exercises of the track with the given name and return its name.
+// An unknown track is an error if it was chosen by the user. Otherwise, it was saved but removed
+// from `info.toml` since then and all exercises are selected.
+fn select_track(
+    exercises: &mut [Exercise],
+    tracks: &[TrackInfo],
+    name: Option<&str>,
+    chosen_by_user: bool,
+) -> Result<Option<&'static str>> {
+    let Some(name) = name.filter(|name| *name != ALL_TRACKS) else {
+        return Ok(None);
+    };
+
+    let Some(track) = tracks.iter().find(|track| track.name == name) else {
+        if !chosen_by_user {
+            return Ok(None);
+        }
+
+        let mut msg = format!("There is no track named `{name}` in `info.toml`. ");
+        if tracks.is_empty() {
+            msg.push_str("These exercises don't have any tracks");
+        } else {
+            msg.push_str("The available tracks: ");
+            for (ind, track) in tracks.iter().enumerate() {
+                if ind > 0 {
+                    msg.push_str(", ");
+                }
+                msg.push_str(track.name);
+            }
+        }
+        bail!(msg);
+    };
+
+    let contained = track.contained_exercises(exercises)?;
+    for (exercise, in_track) in exercises.iter_mut().zip(contained) {
+        exercise.in_track = in_track;
+    }
+
+    Ok(Some(track.name))
+}
+
 const BAD_INDEX_ERR: &str = "The current exercise index is higher than the number of exercises";
-const STATE_FILE_HEADER: &[u8] = b"DON'T EDIT THIS FILE!\n\n";
 const FINISH_LINE: &str = "+----------------------------------------------------+
 |          You made it to the finish line!           |
 +--------------------------  ------------------------+
@@ -617,8 +1190,31 @@ mod tests {
             canonical_path: None,
             test: false,
             strict_clippy: false,
-            hint: "",
+            timeout: None,
+            hints: vec![""],
+            prerequisites: Vec::new(),
+            in_track: true,
             done: false,
+            skipped: false,
+            history: ExerciseHistory::default(),
+        }
+    }
+
+    fn assert_next_pending(
+        app_state: &mut AppState,
+        done: [bool; 3],
+        expected: [Option<usize>; 3],
+    ) {
+        for (exercise, done) in app_state.exercises.iter_mut().zip(done) {
+            exercise.done = done;
+        }
+        for (ind, expected) in expected.into_iter().enumerate() {
+            app_state.current_exercise_ind = ind;
+            assert_eq!(
+                app_state.next_pending_exercise_ind(),
+                expected,
+                "done={done:?}, ind={ind}",
+            );
         }
     }
 
@@ -628,28 +1224,19 @@ mod tests {
             current_exercise_ind: 0,
             exercises: vec![dummy_exercise(), dummy_exercise(), dummy_exercise()],
             n_done: 0,
+            track: None,
             final_message: "",
-            state_file: tempfile::tempfile().unwrap(),
             file_buf: Vec::new(),
             official_exercises: true,
+            read_only: true,
             cmd_runner: CmdRunner::build().unwrap(),
+            check_parallelism: None,
+            use_check_cache: true,
             emit_file_links: true,
             editor: None,
         };
 
-        let mut assert = |done: [bool; 3], expected: [Option<usize>; 3]| {
-            for (exercise, done) in app_state.exercises.iter_mut().zip(done) {
-                exercise.done = done;
-            }
-            for (ind, expected) in expected.into_iter().enumerate() {
-                app_state.current_exercise_ind = ind;
-                assert_eq!(
-                    app_state.next_pending_exercise_ind(),
-                    expected,
-                    "done={done:?}, ind={ind}",
-                );
-            }
-        };
+        let mut assert = |done, expected| assert_next_pending(&mut app_state, done, expected);
 
         assert([true, true, true], [None, None, None]);
         assert([false, false, false], [Some(1), Some(2), Some(0)]);
@@ -659,5 +1246,32 @@ mod tests {
         assert([true, false, false], [Some(1), Some(2), Some(1)]);
         assert([false, true, false], [Some(2), Some(2), Some(0)]);
         assert([false, false, true], [Some(1), Some(0), Some(0)]);
+
+        // The exercise 1 requires the exercise 2.
+        app_state.exercises[1].prerequisites.push(2);
+        let mut assert = |done, expected| assert_next_pending(&mut app_state, done, expected);
+
+        assert([false, false, false], [Some(2), Some(2), Some(0)]);
+        assert([true, false, false], [Some(2), Some(2), Some(1)]);
+        assert([true, false, true], [Some(1), None, Some(1)]);
+        app_state.exercises[1].prerequisites.clear();
+
+        // Skipped exercises are revisited last.
+        app_state.exercises[1].skipped = true;
+        let mut assert = |done, expected| assert_next_pending(&mut app_state, done, expected);
+
+        assert([false, false, false], [Some(2), Some(2), Some(0)]);
+        assert([true, false, false], [Some(2), Some(2), Some(1)]);
+        assert([true, false, true], [Some(1), None, Some(1)]);
+        app_state.exercises[1].skipped = false;
+
+        // Only locked exercises are pending because of a cycle.
+        app_state.exercises[1].prerequisites.push(2);
+        app_state.exercises[2].prerequisites.push(1);
+        assert_next_pending(
+            &mut app_state,
+            [true, false, false],
+            [Some(1), Some(2), Some(1)],
+        );
     }
 }
@@ -0,0 +1,201 @@
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, StdoutLock, Write},
+    path::Path,
+};
+
+use crate::{
+    app_state::AppState,
+    state_file::{self, StateFile},
+};
+
+const FORMAT_VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct ArchivedFile<'a> {
+    /// The path of the exercise file relative to the Rustlings directory like `exercises/…/….rs`.
+    #[serde(borrow)]
+    path: Cow<'a, str>,
+    #[serde(borrow)]
+    content: Cow<'a, str>,
+}
+
+/// A single JSON file containing the state and the modified exercise files.
+#[derive(Serialize, Deserialize)]
+struct Archive<'a> {
+    format_version: u8,
+    #[serde(borrow)]
+    state: StateFile<'a>,
+    #[serde(borrow)]
+    files: Vec<ArchivedFile<'a>>,
+}
+
+// Whether an exercise file with this content was modified by the user.
+// Files of community exercises without a recorded original copy are considered modified.
+fn is_modified(app_state: &AppState, exercise_ind: usize, content: &[u8]) -> bool {
+    !app_state
+        .original_exercise(exercise_ind)
+        .is_ok_and(|original| *content == *original)
+}
+
+// The directory of the exercise might not exist locally (e.g. after a fresh `init`).
+fn write_exercise_file(file: &ArchivedFile) -> Result<()> {
+    if let Some(dir) = Path::new(&*file.path).parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create the directory {}", dir.display()))?;
+    }
+
+    fs::write(&*file.path, file.content.as_bytes())
+        .with_context(|| format!("Failed to write the exercise file {}", file.path))
+}
+
+fn write_n_files(stdout: &mut StdoutLock, n_files: usize) -> io::Result<()> {
+    write!(stdout, "{n_files} exercise file")?;
+    if n_files != 1 {
+        stdout.write_all(b"s")?;
+    }
+
+    Ok(())
+}
+
+pub fn export(app_state: &AppState, archive_path: &Path) -> Result<()> {
+    let mut files = Vec::new();
+    for (exercise_ind, exercise) in app_state.exercises().iter().enumerate() {
+        let content = fs::read_to_string(exercise.path)
+            .with_context(|| format!("Failed to read the exercise file {}", exercise.path))?;
+
+        if is_modified(app_state, exercise_ind, content.as_bytes()) {
+            files.push(ArchivedFile {
+                path: Cow::Borrowed(exercise.path),
+                content: Cow::Owned(content),
+            });
+        }
+    }
+
+    let archive = Archive {
+        format_version: FORMAT_VERSION,
+        state: app_state.state(),
+        files,
+    };
+    let mut archive_buf = serde_json::ser::to_vec_pretty(&archive)
+        .context("Failed to serialize the exported progress")?;
+    archive_buf.push(b'\n');
+    fs::write(archive_path, archive_buf).with_context(|| {
+        format!(
+            "Failed to write the archive file {}",
+            archive_path.display(),
+        )
+    })?;
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(b"Exported the progress and ")?;
+    write_n_files(&mut stdout, archive.files.len())?;
+    writeln!(stdout, " to {}", archive_path.display())?;
+
+    Ok(())
+}
+
+/// Import an archive created by `export`.
+/// Fails without changing anything if a local exercise file was also modified and differs from
+/// the archived one unless `overwrite` is `true`.
+pub fn import(app_state: &mut AppState, archive_path: &Path, overwrite: bool) -> Result<()> {
+    let archive_buf = fs::read(archive_path)
+        .with_context(|| format!("Failed to read the archive file {}", archive_path.display()))?;
+    let archive = serde_json::de::from_slice::<Archive>(&archive_buf).with_context(|| {
+        format!(
+            "Failed to parse the archive file {}",
+            archive_path.display(),
+        )
+    })?;
+    if archive.format_version > FORMAT_VERSION {
+        bail!(
+            "The archive was exported by a newer version of Rustlings. Update Rustlings to import it"
+        );
+    }
+    state_file::check_format_version(archive.state.format_version)?;
+
+    let mut conflicts = Vec::new();
+    // The local contents of the archived files to undo a failed import. `None` if missing.
+    let mut local_contents = Vec::with_capacity(archive.files.len());
+    for file in &archive.files {
+        // Only allow writing exercise files.
+        let exercise_ind = app_state
+            .exercises()
+            .iter()
+            .position(|exercise| exercise.path == file.path)
+            .with_context(|| {
+                format!(
+                    "The archived file {} isn't an exercise file of the current exercises",
+                    file.path,
+                )
+            })?;
+
+        let local_content = match fs::read(&*file.path) {
+            Ok(v) => v,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                local_contents.push(None);
+                continue;
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read the file {}", file.path));
+            }
+        };
+
+        if local_content != file.content.as_bytes()
+            && is_modified(app_state, exercise_ind, &local_content)
+        {
+            conflicts.push(&*file.path);
+        }
+        local_contents.push(Some(local_content));
+    }
+
+    if !conflicts.is_empty() && !overwrite {
+        let mut msg = String::from(
+            "The following exercise files were also modified locally and differ from the archived ones:\n",
+        );
+        for path in conflicts {
+            msg.push_str("  ");
+            msg.push_str(path);
+            msg.push('\n');
+        }
+        msg.push_str(
+            "Nothing was imported. Run the command again with `--overwrite` to replace them",
+        );
+        bail!(msg);
+    }
+
+    // Undo writing the first `n_written` files to not leave a partial import behind.
+    let undo = |n_written: usize| {
+        for (file, local_content) in archive.files[..n_written].iter().zip(&local_contents) {
+            let _ = match local_content {
+                Some(local_content) => fs::write(&*file.path, local_content),
+                None => fs::remove_file(&*file.path),
+            };
+        }
+    };
+    for (ind, file) in archive.files.iter().enumerate() {
+        if let Err(e) = write_exercise_file(file) {
+            undo(ind);
+            return Err(e);
+        }
+    }
+    if let Err(e) = app_state.replace_state(archive.state) {
+        undo(archive.files.len());
+        return Err(e);
+    }
+
+    let mut stdout = io::stdout().lock();
+    write!(
+        stdout,
+        "Imported the progress ({}/{} exercises done) and ",
+        app_state.n_done(),
+        app_state.n_exercises(),
+    )?;
+    write_n_files(&mut stdout, archive.files.len())?;
+    writeln!(stdout, " from {}", archive_path.display())?;
+
+    Ok(())
+}
@@ -88,6 +88,7 @@ pub fn updated_cargo_toml(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::info_file::Hint;
 
     #[test]
     fn test_bins_start_end_ind() {
@@ -110,16 +111,20 @@ mod tests {
                 dir: None,
                 test: true,
                 strict_clippy: true,
-                hint: "",
+                timeout: None,
+                hint: Hint::Single(""),
                 skip_check_unsolved: false,
+                requires: Vec::new(),
             },
             ExerciseInfo {
                 name: "2",
                 dir: Some("d"),
                 test: false,
                 strict_clippy: false,
-                hint: "",
+                timeout: None,
+                hint: Hint::Single(""),
                 skip_check_unsolved: false,
+                requires: Vec::new(),
             },
         ];
 
@@ -0,0 +1,169 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    process::{Command, Stdio},
+};
+
+use crate::{cmd::CmdRunner, exercise::Exercise};
+
+// The results of the last checks by `check-all`.
+const CACHE_PATH: &str = ".rustlings/check-cache.json";
+const CACHE_DIR: &str = ".rustlings";
+// The files next to the exercises which affect checking all of them (e.g. lints).
+const BUILD_FILES: &[&str] = &[
+    "Cargo.toml",
+    "clippy.toml",
+    ".clippy.toml",
+    ".cargo/config.toml",
+    #[cfg(debug_assertions)]
+    "dev/Cargo.toml",
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct Entry {
+    // The hash of the exercise file and the settings which affect checking it.
+    hash: u64,
+    done: bool,
+}
+
+/// The results of checking exercises which stay valid as long as the exercise files,
+/// the build files, Rustlings and the toolchain don't change.
+#[derive(Serialize, Deserialize, Default)]
+pub struct CheckCache {
+    // The versions of Rustlings and the toolchain and the hashes of the build files.
+    // All entries are invalid if it changes.
+    environment: String,
+    // Exercise name → last result.
+    exercises: HashMap<String, Entry>,
+}
+
+impl CheckCache {
+    /// Read the cache for the current environment. It is empty if the environment changed or
+    /// the cache file doesn't exist or is invalid. `None` if the toolchain version is unknown.
+    pub fn load() -> Option<Self> {
+        let environment = environment()?;
+
+        let cache = fs::read(CACHE_PATH)
+            .ok()
+            .and_then(|content| serde_json::from_slice::<Self>(&content).ok())
+            .filter(|cache| cache.environment == environment);
+
+        Some(cache.unwrap_or_else(|| Self {
+            environment,
+            exercises: HashMap::new(),
+        }))
+    }
+
+    /// Whether the exercise is done according to its last check.
+    /// `None` if it wasn't checked with the hash `hash` before.
+    pub fn done(&self, exercise_name: &str, hash: u64) -> Option<bool> {
+        self.exercises
+            .get(exercise_name)
+            .filter(|entry| entry.hash == hash)
+            .map(|entry| entry.done)
+    }
+
+    pub fn insert(&mut self, exercise_name: &str, hash: u64, done: bool) {
+        self.exercises
+            .insert(String::from(exercise_name), Entry { hash, done });
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::create_dir_all(CACHE_DIR)
+            .with_context(|| format!("Failed to create the directory {CACHE_DIR}"))?;
+        let content = serde_json::to_vec(self).context("Failed to serialize the check cache")?;
+        fs::write(CACHE_PATH, content)
+            .with_context(|| format!("Failed to write the check cache {CACHE_PATH}"))
+    }
+}
+
+// The versions of Rustlings and of the Rust toolchain used in the current directory
+// and the hashes of the build files.
+fn environment() -> Option<String> {
+    let output = Command::new("rustc")
+        .arg("-vV")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut environment = format!("rustlings {}\n", env!("CARGO_PKG_VERSION"));
+    environment.push_str(&String::from_utf8_lossy(&output.stdout));
+
+    for path in BUILD_FILES {
+        // Missing files have no hash.
+        if let Ok(content) = fs::read(path) {
+            let mut hash = FNV1A_OFFSET_BASIS;
+            fnv1a(&mut hash, &content);
+            // Writing to a `String` can't fail.
+            let _ = writeln!(environment, "{path} {hash:016x}");
+        }
+    }
+
+    Some(environment)
+}
+
+const FNV1A_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+// 64-bit FNV-1a. Unlike `DefaultHasher`, the hash is stable across Rust versions.
+// It only detects changes. The cache isn't used for results which others rely on.
+fn fnv1a(hash: &mut u64, bytes: &[u8]) {
+    for &byte in bytes {
+        *hash ^= u64::from(byte);
+        *hash = hash.wrapping_mul(0x100_0000_01b3);
+    }
+}
+
+/// The hash of the exercise file and the exercise's settings. `None` if the file can't be read.
+pub fn exercise_hash(exercise: &Exercise, cmd_runner: &CmdRunner) -> Option<u64> {
+    let content = fs::read(exercise.path).ok()?;
+    let timeout_millis = cmd_runner
+        .timeout(exercise.timeout)
+        .map_or(0, |timeout| timeout.as_millis() as u64);
+
+    let mut hash = FNV1A_OFFSET_BASIS;
+    fnv1a(&mut hash, &content);
+    fnv1a(
+        &mut hash,
+        &[u8::from(exercise.test), u8::from(exercise.strict_clippy)],
+    );
+    fnv1a(&mut hash, &timeout_millis.to_le_bytes());
+    Some(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_hash() {
+        let hash = |bytes: &[u8]| {
+            let mut hash = FNV1A_OFFSET_BASIS;
+            fnv1a(&mut hash, bytes);
+            hash
+        };
+
+        // Reference values of 64-bit FNV-1a.
+        assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(hash(b"fn main() {}"), hash(b"fn main() { }"));
+    }
+
+    #[test]
+    fn cached_results() {
+        let mut cache = CheckCache::default();
+        cache.insert("intro1", 1, true);
+        assert_eq!(cache.done("intro1", 1), Some(true));
+        assert_eq!(cache.done("intro1", 2), None);
+        assert_eq!(cache.done("intro2", 1), None);
+
+        cache.insert("intro1", 2, false);
+        assert_eq!(cache.done("intro1", 2), Some(false));
+    }
+}
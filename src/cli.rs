@@ -1,6 +1,10 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use std::path::PathBuf;
 
-use crate::dev::DevCommand;
+use crate::{
+    config::ConfigCommand, dev::DevCommand, notes::NotesCommand, report::ReportFormat, theme::Theme,
+};
 
 /// Rustlings is a collection of small exercises to get you used to writing and reading Rust code
 #[derive(Parser)]
@@ -10,8 +14,11 @@ pub struct Args {
     pub command: Option<Command>,
     /// Disable automatic opening of the current file in VS Code or Zellij.
     /// Ignores `--edit-cmd`
-    #[arg(long)]
+    #[arg(long, overrides_with = "editor")]
     pub no_editor: bool,
+    /// Open the current file in an editor although `no-editor` is set in a config file
+    #[arg(long, overrides_with = "no_editor")]
+    pub editor: bool,
     /// Open the current exercise by running `EDIT_CMD EXERCISE_PATH`.
     /// The command is not allowed to block (e.g. `vim`).
     /// It should communicate with an editor in a different process.
@@ -24,8 +31,55 @@ pub struct Args {
     pub edit_cmd: Option<String>,
     /// Manually run the current exercise using `r` in the watch mode.
     /// Only use this if Rustlings fails to detect exercise file changes
-    #[arg(long)]
+    #[arg(long, overrides_with = "no_manual_run")]
     pub manual_run: bool,
+    /// Run the current exercise on file changes although `manual-run` is set in a config file
+    #[arg(long, overrides_with = "manual_run")]
+    pub no_manual_run: bool,
+    /// Kill an exercise's binary or tests if they run longer than `SECONDS`.
+    /// Overrides the time limits of all exercises (default: 10). `0` disables the limit
+    #[arg(long, global = true, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+    /// Only keep the first `BYTES` of the output of each command (default: 1 MiB).
+    /// `0` disables the limit
+    #[arg(long, global = true, value_name = "BYTES")]
+    pub output_limit: Option<usize>,
+    /// Only work on the exercises of the track `NAME` from `info.toml`.
+    /// The selected track is saved. `--track all` selects all exercises again
+    #[arg(long, global = true, value_name = "NAME")]
+    pub track: Option<String>,
+    /// Run even if another Rustlings instance is running in the same directory.
+    /// The progress saved by one of the instances might be lost
+    #[arg(long, global = true)]
+    pub force: bool,
+    /// Check all exercises when checking all of them instead of only the ones which changed
+    /// since their last check (or whose toolchain changed)
+    #[arg(long, global = true, overrides_with = "cache")]
+    pub no_cache: bool,
+    /// Only check the exercises which changed since their last check although `no-cache` is set
+    /// in a config file
+    #[arg(long, global = true, overrides_with = "no_cache")]
+    pub cache: bool,
+    /// The number of exercises checked in parallel when checking all of them
+    /// (default: the number of CPUs). `0` uses the number of CPUs
+    #[arg(long, global = true, value_name = "N")]
+    pub parallelism: Option<usize>,
+    /// The output format of the commands `run`, `check-all`, `hint` and `stats` (default: text).
+    /// `json` prints a machine-readable document instead of colored text
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<OutputFormat>,
+    /// The colors of the output. `high-contrast` is readable on light and dark backgrounds
+    /// and with color blindness. `mono` only uses attributes like bold and symbols.
+    /// Defaults to `mono` if the environment variable `NO_COLOR` is set
+    #[arg(long, global = true, value_enum)]
+    pub theme: Option<Theme>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Text,
+    Json,
 }
 
 #[derive(Subcommand)]
@@ -38,19 +92,81 @@ pub enum Command {
         /// The name of the exercise
         name: Option<String>,
     },
-    /// Check all the exercises, marking them as done or pending accordingly
-    CheckAll,
-    /// Reset a single exercise
+    /// Check all the exercises, marking them as done or pending accordingly.
+    /// Exits with 0 if all exercises are done and 1 if some are pending
+    CheckAll {
+        /// Work without a terminal (e.g. in CI): Print one line per exercise instead of
+        /// showing the progress, skip the welcome message and exit with 2 on internal errors
+        #[arg(long)]
+        headless: bool,
+        /// Write a report of the exercises' states to `PATH`
+        #[arg(long, value_name = "PATH")]
+        report: Option<PathBuf>,
+        /// The format of the report
+        #[arg(long, value_enum, default_value_t = ReportFormat::Junit, requires = "report")]
+        report_format: ReportFormat,
+    },
+    /// Reset an exercise, all exercises matching a pattern, a directory or all exercises.
+    /// Asks for confirmation before resetting multiple exercises
     Reset {
-        /// The name of the exercise
-        name: String,
+        /// The name of the exercise or a pattern like `iterators*` where `*` matches any
+        /// characters and `?` matches a single character
+        #[arg(required_unless_present_any = ["dir", "all"], conflicts_with_all = ["dir", "all"])]
+        name: Option<String>,
+        /// Reset all exercises in a directory like `06_move_semantics`
+        #[arg(long, conflicts_with = "all")]
+        dir: Option<String>,
+        /// Reset all exercises
+        #[arg(long)]
+        all: bool,
+        /// Don't ask for confirmation before resetting multiple exercises
+        #[arg(short, long)]
+        yes: bool,
+        /// Restore the changes discarded by the last reset of the exercise instead
+        #[arg(long, requires = "name", conflicts_with = "yes")]
+        undo: bool,
     },
     /// Show a hint.
-    /// Shows the hint of the next pending exercise if the exercise name is not specified
+    /// Shows the hint of the next pending exercise if the exercise name is not specified.
+    /// Hints with multiple levels are revealed one level per call
     Hint {
         /// The name of the exercise
         name: Option<String>,
+        /// Show the hint level `N` (starting at 1) instead of the next one not revealed yet
+        #[arg(long, value_name = "N")]
+        level: Option<u32>,
     },
+    /// Compare a done exercise with its solution ignoring whitespace and comments.
+    /// Compares the current exercise if the exercise name is not specified
+    Diff {
+        /// The name of the exercise
+        name: Option<String>,
+    },
+    /// Show statistics about your progress per topic, time to solve exercises, runs and viewed hints
+    Stats,
+    /// Export your progress and modified exercise files into a single file.
+    /// Import it on another machine with `rustlings import`
+    Export {
+        /// The path of the file to create
+        archive: PathBuf,
+    },
+    /// Import the progress and exercise files exported by `rustlings export`.
+    /// Replaces the current progress
+    Import {
+        /// The path of the exported file
+        archive: PathBuf,
+        /// Replace local exercise files even if they were also modified and differ from the
+        /// exported ones
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Commands for the notes you wrote on exercises in the watch mode
+    #[command(subcommand)]
+    Notes(NotesCommand),
+    /// Show or change the settings in the config files.
+    /// They provide defaults for the command line arguments
+    #[command(subcommand)]
+    Config(ConfigCommand),
     /// Commands for developing (community) Rustlings exercises
     #[command(subcommand)]
     Dev(DevCommand),
@@ -1,49 +1,398 @@
 use anyhow::{Context, Result, bail};
+use crossterm::style::{Attribute, ResetColor, SetAttribute};
 use serde::Deserialize;
+#[cfg(unix)]
+use std::sync::{Mutex, PoisonError};
 use std::{
-    io::{Read, pipe},
+    io::{self, Read, Write, pipe},
+    mem,
     path::PathBuf,
-    process::{Command, Stdio},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+    thread,
+    time::{Duration, Instant},
 };
 
-/// Run a command with a description for a possible error and append the merged stdout and stderr.
-/// The boolean in the returned `Result` is true if the command's exit status is success.
-fn run_cmd(mut cmd: Command, description: &str, output: Option<&mut Vec<u8>>) -> Result<bool> {
+use crate::{
+    diagnostics::{self, Diagnostic},
+    term::write_ansi,
+    theme::{self, Style, set_style_ansi},
+};
+
+/// The default time limit in seconds for running an exercise's binary or tests.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 10;
+/// The default limit of the captured output of a single command in bytes (1 MiB).
+pub const DEFAULT_OUTPUT_LIMIT: usize = 1 << 20;
+
+// How often the watchdog checks if a command exited.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// Running commands are killed and new ones aren't started while this is set.
+static CANCEL: AtomicBool = AtomicBool::new(false);
+
+/// Kill all running commands and don't start new ones until `reset_cancel` is called.
+pub fn cancel() {
+    CANCEL.store(true, Relaxed);
+}
+
+/// Whether running commands were cancelled since the last call of `reset_cancel`.
+pub fn cancelled() -> bool {
+    CANCEL.load(Relaxed)
+}
+
+pub fn reset_cancel() {
+    CANCEL.store(false, Relaxed);
+}
+
+/// How a command exited.
+pub enum CmdExit {
+    Status(ExitStatus),
+    /// The command was killed because it didn't exit within the time limit.
+    TimedOut(Duration),
+    /// The command was killed or not even started because of `cancel`.
+    Cancelled,
+}
+
+impl CmdExit {
+    pub fn success(&self) -> bool {
+        matches!(self, Self::Status(status) if status.success())
+    }
+
+    /// `None` if the process was terminated by a signal, timed out or was cancelled.
+    pub fn code(&self) -> Option<i32> {
+        match self {
+            Self::Status(status) => status.code(),
+            Self::TimedOut(_) | Self::Cancelled => None,
+        }
+    }
+}
+
+// The process groups of the running commands (see `spawn`).
+#[cfg(unix)]
+static PROCESS_GROUPS: Mutex<Vec<rustix::process::Pid>> = Mutex::new(Vec::new());
+
+/// Forward the signals for interrupting and terminating Rustlings (e.g. Ctrl+C) to the running
+/// commands and terminate afterwards. The terminal only signals its foreground process group
+/// which doesn't contain the commands because each one runs in its own process group.
+#[cfg(unix)]
+pub fn forward_termination_signals() -> Result<()> {
+    use rustix::process::{Signal, kill_process_group};
+    use signal_hook::{
+        consts::{SIGHUP, SIGINT, SIGTERM},
+        iterator::Signals,
+        low_level::emulate_default_handler,
+    };
+
+    let mut signals =
+        Signals::new([SIGINT, SIGTERM, SIGHUP]).context("Failed to register signal handlers")?;
+
+    thread::Builder::new()
+        .spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                // Held until terminating to not spawn new commands.
+                let process_groups = PROCESS_GROUPS
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                let forwarded = match signal {
+                    SIGINT => Signal::INT,
+                    SIGTERM => Signal::TERM,
+                    _ => Signal::HUP,
+                };
+                for &process_group in process_groups.iter() {
+                    let _ = kill_process_group(process_group, forwarded);
+                }
+
+                let _ = emulate_default_handler(signal);
+            }
+        })
+        .context("Failed to spawn a thread to forward signals")?;
+
+    Ok(())
+}
+
+/// The terminal signals all processes of the console including the commands.
+#[cfg(not(unix))]
+pub fn forward_termination_signals() -> Result<()> {
+    Ok(())
+}
+
+// A spawned command which is unregistered from the forwarded signals when dropped.
+struct Spawned {
+    child: Child,
+}
+
+impl Drop for Spawned {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        {
+            let process_group = rustix::process::Pid::from_child(&self.child);
+            PROCESS_GROUPS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .retain(|&pid| pid != process_group);
+        }
+    }
+}
+
+// Spawn the child in its own process group to be able to kill the whole process tree.
+// The process group is registered to forward termination signals to it.
+fn spawn(cmd: &mut Command) -> io::Result<Spawned> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        cmd.process_group(0);
+        let mut process_groups = PROCESS_GROUPS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let child = cmd.spawn()?;
+        process_groups.push(rustix::process::Pid::from_child(&child));
+        Ok(Spawned { child })
+    }
+
+    #[cfg(not(unix))]
+    cmd.spawn().map(|child| Spawned { child })
+}
+
+// Kill the child and all processes it spawned (e.g. the compiler spawned by Cargo).
+// Fails if the child exited in the meantime which is fine.
+#[cfg(unix)]
+fn kill_tree(child: &mut Child) {
+    // The child is the leader of its process group (see `new_process_group`).
+    let process_group = rustix::process::Pid::from_child(child);
+    if rustix::process::kill_process_group(process_group, rustix::process::Signal::KILL).is_err() {
+        let _ = child.kill();
+    }
+}
+
+#[cfg(windows)]
+fn kill_tree(child: &mut Child) {
+    // `taskkill /T` kills the process tree without needing a job object.
+    let killed = Command::new("taskkill")
+        .args(["/T", "/F", "/PID"])
+        .arg(child.id().to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if !killed {
+        let _ = child.kill();
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn kill_tree(child: &mut Child) {
+    let _ = child.kill();
+}
+
+// Wait for the child to exit while `read` reads its output on the calling thread.
+// A watchdog thread kills the child's process tree when it exceeds the timeout or when the
+// commands are cancelled. This also closes the output pipe if grandchildren inherited it.
+fn wait_killable<T>(
+    child: &mut Child,
+    description: &str,
+    timeout: Option<Duration>,
+    read: impl FnOnce() -> Result<T>,
+) -> Result<(CmdExit, T)> {
+    let wait_err = || format!("Failed to wait on the command `{description}` to exit");
+
+    let start = Instant::now();
+    thread::scope(|s| {
+        let watchdog = thread::Builder::new()
+            .spawn_scoped(s, || -> Result<CmdExit> {
+                loop {
+                    if let Some(status) = child.try_wait().with_context(wait_err)? {
+                        return Ok(CmdExit::Status(status));
+                    }
+
+                    let exit = if cancelled() {
+                        CmdExit::Cancelled
+                    } else if let Some(timeout) = timeout
+                        && start.elapsed() >= timeout
+                    {
+                        CmdExit::TimedOut(timeout)
+                    } else {
+                        thread::sleep(WAIT_POLL_INTERVAL);
+                        continue;
+                    };
+
+                    kill_tree(child);
+                    child.wait().with_context(wait_err)?;
+                    return Ok(exit);
+                }
+            })
+            .context("Failed to spawn a thread to wait on a command")?;
+
+        let value = read()?;
+        let exit = watchdog.join().unwrap()?;
+
+        Ok((exit, value))
+    })
+}
+
+/// Print output which was just appended to a buffer to stdout.
+pub fn print_streamed(appended: &[u8]) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(appended)?;
+    stdout.flush()
+}
+
+// The captured output of a command. Only up to `limit` bytes are kept.
+// If `stream` is set, the kept output is also printed as soon as it arrives.
+struct CapturedOutput<'out> {
+    output: &'out mut Vec<u8>,
+    limit: Option<usize>,
+    remaining: usize,
+    n_discarded: u64,
+    stream: bool,
+}
+
+impl<'out> CapturedOutput<'out> {
+    fn new(output: &'out mut Vec<u8>, limit: Option<usize>, stream: bool) -> Self {
+        Self {
+            output,
+            limit,
+            remaining: limit.unwrap_or(usize::MAX),
+            n_discarded: 0,
+            stream,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let n_kept = bytes.len().min(self.remaining);
+        self.remaining -= n_kept;
+        self.n_discarded += (bytes.len() - n_kept) as u64;
+
+        if n_kept > 0 {
+            self.output.extend_from_slice(&bytes[..n_kept]);
+            if self.stream {
+                print_streamed(&bytes[..n_kept])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Tell if the output was cut off at the limit and end it with a newline.
+    fn finish(self) -> io::Result<()> {
+        let end = self.output.len();
+        if self.n_discarded > 0 {
+            self.output.push(b'\n');
+            write_ansi(self.output, SetAttribute(Attribute::Bold));
+            set_style_ansi(self.output, Style::Warning);
+            // Writing to a `Vec` can't fail.
+            let _ = write!(
+                self.output,
+                "… {} bytes of output omitted (limit: {} bytes)",
+                self.n_discarded,
+                self.limit.unwrap_or_default(),
+            );
+            write_ansi(self.output, ResetColor);
+        }
+
+        self.output.push(b'\n');
+
+        if self.stream {
+            print_streamed(&self.output[end..])?;
+        }
+
+        Ok(())
+    }
+}
+
+// Handles output of a command as it arrives.
+type OnOutput<'a> = &'a mut dyn FnMut(&[u8]) -> io::Result<()>;
+
+// Read until EOF and pass every chunk to `on_chunk` as soon as it arrives.
+// Keeps reading after the output limit is reached to not block the command on a full pipe.
+fn read_output(mut reader: impl Read, on_chunk: OnOutput) -> io::Result<()> {
+    let mut chunk = [0; 1 << 12];
+
+    loop {
+        let n = match reader.read(&mut chunk) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        on_chunk(&chunk[..n])?;
+    }
+}
+
+// Run a command with a description for a possible error and pass its merged stdout and stderr
+// to `on_output` as it arrives. The output is discarded without `on_output`.
+// The command is killed if it doesn't exit within the `timeout` or if the commands are cancelled.
+fn run_piped(
+    mut cmd: Command,
+    description: &str,
+    timeout: Option<Duration>,
+    on_output: Option<OnOutput>,
+) -> Result<CmdExit> {
+    if cancelled() {
+        return Ok(CmdExit::Cancelled);
+    }
+
     let spawn = |mut cmd: Command| {
         // NOTE: The closure drops `cmd` which prevents a pipe deadlock.
-        cmd.stdin(Stdio::null())
-            .spawn()
-            .with_context(|| format!("Failed to run the command `{description}`"))
+        cmd.stdin(Stdio::null());
+        spawn(&mut cmd).with_context(|| format!("Failed to run the command `{description}`"))
     };
 
-    let mut handle = if let Some(output) = output {
-        let (mut reader, writer) = pipe().with_context(|| {
-            format!("Failed to create a pipe to run the command `{description}``")
-        })?;
+    let Some(on_output) = on_output else {
+        cmd.stdout(Stdio::null()).stderr(Stdio::null());
+        let mut spawned = spawn(cmd)?;
+        let (exit, ()) = wait_killable(&mut spawned.child, description, timeout, || Ok(()))?;
+        return Ok(exit);
+    };
 
-        let writer_clone = writer.try_clone().with_context(|| {
-            format!("Failed to clone the pipe writer for the command `{description}`")
-        })?;
+    let (reader, writer) = pipe()
+        .with_context(|| format!("Failed to create a pipe to run the command `{description}`"))?;
 
-        cmd.stdout(writer_clone).stderr(writer);
-        let handle = spawn(cmd)?;
+    let writer_clone = writer.try_clone().with_context(|| {
+        format!("Failed to clone the pipe writer for the command `{description}`")
+    })?;
 
-        reader
-            .read_to_end(output)
-            .with_context(|| format!("Failed to read the output of the command `{description}`"))?;
+    cmd.stdout(writer_clone).stderr(writer);
+    let mut spawned = spawn(cmd)?;
 
-        output.push(b'\n');
+    let (exit, ()) = wait_killable(&mut spawned.child, description, timeout, || {
+        read_output(reader, on_output)
+            .with_context(|| format!("Failed to read the output of the command `{description}`"))
+    })?;
 
-        handle
-    } else {
-        cmd.stdout(Stdio::null()).stderr(Stdio::null());
-        spawn(cmd)?
+    Ok(exit)
+}
+
+/// Run a command with a description for a possible error and append the merged stdout and stderr.
+/// The captured output is limited to `output_limit` bytes.
+/// The command is killed if it doesn't exit within the `timeout` or if the commands are cancelled.
+/// If `stream` is set, the output is also printed as it arrives.
+fn run_cmd(
+    cmd: Command,
+    description: &str,
+    output: Option<&mut Vec<u8>>,
+    output_limit: Option<usize>,
+    timeout: Option<Duration>,
+    stream: bool,
+) -> Result<CmdExit> {
+    let Some(output) = output else {
+        return run_piped(cmd, description, timeout, None);
     };
 
-    handle
-        .wait()
-        .with_context(|| format!("Failed to wait on the command `{description}` to exit"))
-        .map(|status| status.success())
+    let mut captured = CapturedOutput::new(output, output_limit, stream);
+    let exit = run_piped(
+        cmd,
+        description,
+        timeout,
+        Some(&mut |chunk| captured.push(chunk)),
+    )?;
+    captured
+        .finish()
+        .context("Failed to print the output of a command")?;
+
+    Ok(exit)
 }
 
 // Parses parts of the output of `cargo metadata`.
@@ -52,8 +401,145 @@ struct CargoMetadata {
     target_directory: PathBuf,
 }
 
+// Parses the lines of the output of Cargo with `--message-format json…`.
+#[derive(Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+enum CargoMessage {
+    CompilerMessage {
+        message: Diagnostic,
+    },
+    CompilerArtifact {
+        profile: ArtifactProfile,
+        executable: Option<PathBuf>,
+        manifest_path: PathBuf,
+    },
+    // Build scripts, build finished, etc.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct ArtifactProfile {
+    test: bool,
+}
+
+// A test executable built by Cargo.
+struct TestExecutable {
+    path: PathBuf,
+    // The `Cargo.toml` file of the package.
+    manifest_path: PathBuf,
+}
+
+// Parses the output of Cargo running with `--message-format json-diagnostic-rendered-ansi`
+// line by line as it arrives.
+// Diagnostics are appended to `diagnostics` if provided.
+// Otherwise, their rendered form is appended to `output` like without JSON messages.
+// Plain lines (e.g. errors of Cargo itself) are appended to `output`.
+struct CargoMessageParser<'a, 'out> {
+    output: Option<&'a mut CapturedOutput<'out>>,
+    diagnostics: Option<&'a mut Vec<Diagnostic>>,
+    // Print the rendered form of collected diagnostics as soon as they arrive.
+    stream_diagnostics: bool,
+    // The incomplete last line.
+    line: Vec<u8>,
+    // The last built test executable.
+    test_executable: Option<TestExecutable>,
+}
+
+impl<'a, 'out> CargoMessageParser<'a, 'out> {
+    fn new(
+        output: Option<&'a mut CapturedOutput<'out>>,
+        diagnostics: Option<&'a mut Vec<Diagnostic>>,
+        stream_diagnostics: bool,
+    ) -> Self {
+        Self {
+            output,
+            diagnostics,
+            stream_diagnostics,
+            line: Vec::with_capacity(1 << 12),
+            test_executable: None,
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) -> io::Result<()> {
+        for part in chunk.split_inclusive(|c| *c == b'\n') {
+            self.line.extend_from_slice(part);
+
+            if part.ends_with(b"\n") {
+                // Taken to parse the line which borrows `self`.
+                let line = mem::take(&mut self.line);
+                let res = self.parse_line(&line);
+                self.line = line;
+                self.line.clear();
+                res?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_line(&mut self, line: &[u8]) -> io::Result<()> {
+        if line.first() == Some(&b'{')
+            && let Ok(message) = serde_json::de::from_slice::<CargoMessage>(line)
+        {
+            match message {
+                CargoMessage::CompilerMessage {
+                    message: diagnostic,
+                } => {
+                    if let Some(diagnostics) = self.diagnostics.as_deref_mut() {
+                        let rendered = diagnostic.rendered.clone();
+                        if diagnostics::push_unique(diagnostics, diagnostic)
+                            && self.stream_diagnostics
+                            && let Some(rendered) = rendered
+                        {
+                            print_streamed(rendered.as_bytes())?;
+                        }
+                    } else if let Some(output) = self.output.as_deref_mut()
+                        && let Some(rendered) = &diagnostic.rendered
+                    {
+                        output.push(rendered.as_bytes())?;
+                    }
+                }
+                CargoMessage::CompilerArtifact {
+                    profile,
+                    executable: Some(path),
+                    manifest_path,
+                } if profile.test => {
+                    self.test_executable = Some(TestExecutable {
+                        path,
+                        manifest_path,
+                    });
+                }
+                _ => (),
+            }
+
+            return Ok(());
+        }
+
+        if let Some(output) = self.output.as_deref_mut() {
+            output.push(line)?;
+        }
+
+        Ok(())
+    }
+
+    // Parse the last line if it doesn't end with a newline and return the last test executable.
+    fn finish(mut self) -> io::Result<Option<TestExecutable>> {
+        if !self.line.is_empty() {
+            let line = mem::take(&mut self.line);
+            self.parse_line(&line)?;
+        }
+
+        Ok(self.test_executable)
+    }
+}
+
 pub struct CmdRunner {
     target_dir: PathBuf,
+    parse_diagnostics: bool,
+    timeout_secs: Option<u64>,
+    output_limit: Option<usize>,
+    stream_output: bool,
 }
 
 impl CmdRunner {
@@ -81,9 +567,50 @@ impl CmdRunner {
 
         Ok(Self {
             target_dir: metadata.target_directory,
+            parse_diagnostics: false,
+            timeout_secs: None,
+            output_limit: Some(DEFAULT_OUTPUT_LIMIT),
+            stream_output: false,
         })
     }
 
+    /// Let Cargo emit JSON diagnostics to parse them instead of capturing the rendered ones.
+    /// Only affects Cargo commands with captured output and provided diagnostics.
+    pub fn parse_diagnostics(&mut self) {
+        self.parse_diagnostics = true;
+    }
+
+    /// Print captured output to stdout as soon as it arrives. It is still captured.
+    pub fn stream_output(&mut self) {
+        self.stream_output = true;
+    }
+
+    pub fn streams_output(&self) -> bool {
+        self.stream_output
+    }
+
+    /// Override the time limit of all exercises. `0` disables the limit.
+    pub fn set_timeout(&mut self, secs: u64) {
+        self.timeout_secs = Some(secs);
+    }
+
+    /// Limit the captured output of a single command. `0` disables the limit.
+    pub fn set_output_limit(&mut self, bytes: usize) {
+        self.output_limit = (bytes > 0).then_some(bytes);
+    }
+
+    /// The time limit for running an exercise's binary or tests.
+    /// An override has priority over the exercise's own limit from `info.toml`.
+    /// `None` if the limit is disabled.
+    pub fn timeout(&self, exercise_timeout_secs: Option<u64>) -> Option<Duration> {
+        let secs = self
+            .timeout_secs
+            .or(exercise_timeout_secs)
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+
+        (secs > 0).then(|| Duration::from_secs(secs))
+    }
+
     pub fn cargo<'out>(
         &self,
         subcommand: &str,
@@ -101,14 +628,72 @@ impl CmdRunner {
             .arg(&self.target_dir);
 
         if output.is_some() {
-            cmd.arg("--color").arg("always");
+            cmd.arg("--color").arg(theme::cargo_color());
+        }
+
+        CargoSubcommand {
+            cmd,
+            output,
+            diagnostics: None,
+            json_messages: false,
+            parse_diagnostics: self.parse_diagnostics,
+            output_limit: self.output_limit,
+            stream_output: self.stream_output,
+        }
+    }
+
+    /// Build the tests with `cargo test --no-run` and run the test executable directly.
+    /// Running it without Cargo in between allows killing it when it exceeds the `timeout`.
+    pub fn test(
+        &self,
+        bin_name: &str,
+        mut output: Option<&mut Vec<u8>>,
+        diagnostics: &mut Vec<Diagnostic>,
+        timeout: Option<Duration>,
+    ) -> Result<CmdExit> {
+        let output_is_some = output.is_some();
+
+        let mut build_cmd = self.cargo("test", bin_name, output.as_deref_mut());
+        build_cmd.diagnostics(diagnostics).json_messages();
+        build_cmd.args(["--no-run"]);
+        let (exit, test_executable) = build_cmd.run_with_messages("cargo test --no-run …")?;
+        if !exit.success() {
+            return Ok(exit);
+        }
+
+        let test_executable = test_executable
+            .context("Cargo didn't report the path of the test executable of `cargo test …`")?;
+        let mut test_cmd = Command::new(&test_executable.path);
+        // Like `cargo test` which runs the tests in the package's directory.
+        if let Some(package_dir) = test_executable
+            .manifest_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            test_cmd
+                .current_dir(package_dir)
+                .env("CARGO_MANIFEST_DIR", package_dir);
+        }
+        if output_is_some {
+            test_cmd.args(["--color", theme::cargo_color(), "--format", "pretty"]);
         }
 
-        CargoSubcommand { cmd, output }
+        run_cmd(
+            test_cmd,
+            &test_executable.path.to_string_lossy(),
+            output,
+            self.output_limit,
+            timeout,
+            self.stream_output,
+        )
     }
 
-    /// The boolean in the returned `Result` is true if the command's exit status is success.
-    pub fn run_debug_bin(&self, bin_name: &str, output: Option<&mut Vec<u8>>) -> Result<bool> {
+    pub fn run_debug_bin(
+        &self,
+        bin_name: &str,
+        output: Option<&mut Vec<u8>>,
+        timeout: Option<Duration>,
+    ) -> Result<CmdExit> {
         // 7 = "/debug/".len()
         let mut bin_path =
             PathBuf::with_capacity(self.target_dir.as_os_str().len() + 7 + bin_name.len());
@@ -116,16 +701,51 @@ impl CmdRunner {
         bin_path.push("debug");
         bin_path.push(bin_name);
 
-        run_cmd(Command::new(&bin_path), &bin_path.to_string_lossy(), output)
+        run_cmd(
+            Command::new(&bin_path),
+            &bin_path.to_string_lossy(),
+            output,
+            self.output_limit,
+            timeout,
+            self.stream_output,
+        )
     }
 }
 
 pub struct CargoSubcommand<'out> {
     cmd: Command,
     output: Option<&'out mut Vec<u8>>,
+    diagnostics: Option<&'out mut Vec<Diagnostic>>,
+    json_messages: bool,
+    parse_diagnostics: bool,
+    output_limit: Option<usize>,
+    stream_output: bool,
 }
 
-impl CargoSubcommand<'_> {
+impl<'out> CargoSubcommand<'out> {
+    // Let Cargo emit JSON messages.
+    fn json_messages(&mut self) -> &mut Self {
+        if !self.json_messages {
+            self.cmd
+                .arg("--message-format")
+                .arg("json-diagnostic-rendered-ansi");
+            self.json_messages = true;
+        }
+
+        self
+    }
+
+    /// Collect the diagnostics if the command runner parses them.
+    /// Has to be called before adding arguments after `--`.
+    pub fn diagnostics(&mut self, diagnostics: &'out mut Vec<Diagnostic>) -> &mut Self {
+        if self.parse_diagnostics && self.output.is_some() {
+            self.json_messages();
+            self.diagnostics = Some(diagnostics);
+        }
+
+        self
+    }
+
     pub fn args<'arg, I>(&mut self, args: I) -> &mut Self
     where
         I: IntoIterator<Item = &'arg str>,
@@ -134,9 +754,49 @@ impl CargoSubcommand<'_> {
         self
     }
 
-    /// The boolean in the returned `Result` is true if the command's exit status is success.
-    pub fn run(self, description: &str) -> Result<bool> {
-        run_cmd(self.cmd, description, self.output)
+    // Also returns the test executable if JSON messages are enabled.
+    fn run_with_messages(self, description: &str) -> Result<(CmdExit, Option<TestExecutable>)> {
+        if !self.json_messages {
+            let exit = run_cmd(
+                self.cmd,
+                description,
+                self.output,
+                self.output_limit,
+                None,
+                self.stream_output,
+            )?;
+            return Ok((exit, None));
+        }
+
+        // The JSON messages are needed even if the output isn't.
+        // The output limit only applies to the rendered output since cutting off the messages
+        // would lose the test executable.
+        let mut output = self
+            .output
+            .map(|output| CapturedOutput::new(output, self.output_limit, self.stream_output));
+        let stream_diagnostics = output.is_some() && self.stream_output;
+        let mut parser =
+            CargoMessageParser::new(output.as_mut(), self.diagnostics, stream_diagnostics);
+        let exit = run_piped(
+            self.cmd,
+            description,
+            None,
+            Some(&mut |chunk| parser.push(chunk)),
+        )?;
+        let test_executable = parser
+            .finish()
+            .context("Failed to print the output of a command")?;
+        if let Some(output) = output {
+            output
+                .finish()
+                .context("Failed to print the output of a command")?;
+        }
+
+        Ok((exit, test_executable))
+    }
+
+    pub fn run(self, description: &str) -> Result<CmdExit> {
+        self.run_with_messages(description).map(|(exit, _)| exit)
     }
 }
 
@@ -154,8 +814,115 @@ mod tests {
         cmd.arg("Hello");
 
         let mut output = Vec::with_capacity(8);
-        run_cmd(cmd, "echo …", Some(&mut output)).unwrap();
+        run_cmd(cmd, "echo …", Some(&mut output), None, None, false).unwrap();
 
         assert_eq!(output, b"Hello\n\n");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_cmd_limits() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10");
+        let exit = run_cmd(
+            cmd,
+            "sleep …",
+            None,
+            None,
+            Some(Duration::from_millis(50)),
+            false,
+        )
+        .unwrap();
+        assert!(matches!(exit, CmdExit::TimedOut(_)));
+
+        // The grandchild inherits the output pipe and has to be killed too.
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("sleep 10 & wait");
+        let start = Instant::now();
+        let exit = run_cmd(
+            cmd,
+            "sh …",
+            Some(&mut Vec::new()),
+            None,
+            Some(Duration::from_millis(50)),
+            false,
+        )
+        .unwrap();
+        assert!(matches!(exit, CmdExit::TimedOut(_)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let mut cmd = Command::new("echo");
+        cmd.arg("Hello, world");
+        let mut output = Vec::with_capacity(64);
+        let exit = run_cmd(cmd, "echo …", Some(&mut output), Some(5), None, false).unwrap();
+        assert!(exit.success());
+        assert!(output.starts_with(b"Hello\n\x1b"));
+        assert!(output.ends_with(b"\n"));
+    }
+
+    const UNUSED_VARIABLE: &str = r#"{"reason":"compiler-message","message":{"message":"unused variable: `x`","code":{"code":"unused_variables","explanation":null},"level":"warning","spans":[{"file_name":"exercises/a.rs","byte_start":16,"byte_end":17,"line_start":2,"line_end":2,"column_start":9,"column_end":10,"is_primary":true,"text":[{"text":"    let x = 1;","highlight_start":9,"highlight_end":10}],"label":null,"suggested_replacement":null}],"children":[{"message":"if this is intentional, prefix it with an underscore","code":null,"level":"help","spans":[{"file_name":"exercises/a.rs","byte_start":16,"byte_end":17,"line_start":2,"line_end":2,"column_start":9,"column_end":10,"is_primary":true,"text":[],"label":null,"suggested_replacement":"_x"}],"children":[],"rendered":null}],"rendered":"warning: unused variable: `x`\n"}}"#;
+    const TEST_ARTIFACT: &str = r#"{"reason":"compiler-artifact","package_id":"a","manifest_path":"Cargo.toml","target":{"kind":["bin"],"name":"a"},"profile":{"opt_level":"0","debuginfo":2,"debug_assertions":true,"overflow_checks":true,"test":true},"features":[],"filenames":["target/debug/deps/a-0123"],"executable":"target/debug/deps/a-0123","fresh":false}"#;
+
+    // Parse the output in small chunks like lines arriving in parts.
+    fn parse_cargo_messages(
+        cargo_output: &[u8],
+        output: Option<&mut Vec<u8>>,
+        diagnostics: Option<&mut Vec<Diagnostic>>,
+    ) -> Option<TestExecutable> {
+        let mut output = output.map(|output| CapturedOutput::new(output, None, false));
+        let mut parser = CargoMessageParser::new(output.as_mut(), diagnostics, false);
+        for chunk in cargo_output.chunks(7) {
+            parser.push(chunk).unwrap();
+        }
+        parser.finish().unwrap()
+    }
+
+    #[test]
+    fn parse_and_collapse_duplicates() {
+        let cargo_output = format!(
+            "{UNUSED_VARIABLE}\n\
+             {{\"reason\":\"build-finished\",\"success\":true}}\n\
+             error: plain line\n\
+             {UNUSED_VARIABLE}\n\
+             {TEST_ARTIFACT}\n\
+             {{\"reason\":\"compiler-message\",\"message\":{{\"message\":\"aborting due to 1 previous error\",\"code\":null,\"level\":\"error\",\"spans\":[],\"children\":[],\"rendered\":null}}}}\n"
+        );
+
+        let mut output = Vec::new();
+        let mut diagnostics = Vec::new();
+        for _ in 0..2 {
+            let test_executable = parse_cargo_messages(
+                cargo_output.as_bytes(),
+                Some(&mut output),
+                Some(&mut diagnostics),
+            );
+            let test_executable = test_executable.unwrap();
+            assert_eq!(
+                test_executable.path,
+                std::path::Path::new("target/debug/deps/a-0123"),
+            );
+            assert_eq!(
+                test_executable.manifest_path,
+                std::path::Path::new("Cargo.toml"),
+            );
+        }
+
+        assert_eq!(output, b"error: plain line\nerror: plain line\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, "warning");
+        assert_eq!(
+            diagnostics[0].children[0].spans[0]
+                .suggested_replacement
+                .as_deref(),
+            Some("_x"),
+        );
+
+        // Without collecting the diagnostics, their rendered form is kept.
+        output.clear();
+        parse_cargo_messages(cargo_output.as_bytes(), Some(&mut output), None);
+        assert_eq!(
+            output,
+            b"warning: unused variable: `x`\nerror: plain line\nwarning: unused variable: `x`\n",
+        );
+    }
 }
@@ -0,0 +1,584 @@
+use anyhow::{Context, Result, bail};
+use clap::{Subcommand, ValueEnum};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    cli::Args,
+    keymap::{KeyList, KeymapConfig},
+    theme::Theme,
+};
+
+/// The project's config file in the Rustlings directory next to `info.toml`.
+pub const PROJECT_CONFIG_PATH: &str = ".rustlings-config.toml";
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Bool,
+    Integer,
+    String,
+}
+
+// The settings with their type in the order shown by `config show`.
+// Each one can also be set with the environment variable `RUSTLINGS_NAME`.
+const SETTINGS: &[(&str, Kind)] = &[
+    ("no-editor", Kind::Bool),
+    ("edit-cmd", Kind::String),
+    ("manual-run", Kind::Bool),
+    ("timeout", Kind::Integer),
+    ("output-limit", Kind::Integer),
+    ("parallelism", Kind::Integer),
+    ("no-cache", Kind::Bool),
+    ("track", Kind::String),
+    ("theme", Kind::String),
+];
+
+/// Defaults for the command line arguments and further settings. Read from config files,
+/// environment variables and the command line arguments.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub no_editor: Option<bool>,
+    pub edit_cmd: Option<String>,
+    pub manual_run: Option<bool>,
+    pub timeout: Option<u64>,
+    pub output_limit: Option<usize>,
+    /// The number of exercises checked in parallel. `0` uses the number of CPUs.
+    pub parallelism: Option<usize>,
+    pub no_cache: Option<bool>,
+    pub track: Option<String>,
+    pub theme: Option<Theme>,
+    pub keymap: KeymapConfig,
+}
+
+impl Config {
+    pub fn from_args(args: &Args) -> Self {
+        Self {
+            no_editor: flag(args.no_editor, args.editor),
+            edit_cmd: args.edit_cmd.clone(),
+            manual_run: flag(args.manual_run, args.no_manual_run),
+            timeout: args.timeout,
+            output_limit: args.output_limit,
+            parallelism: args.parallelism,
+            no_cache: flag(args.no_cache, args.cache),
+            track: args.track.clone(),
+            theme: args.theme,
+            keymap: KeymapConfig::default(),
+        }
+    }
+
+    fn read(path: &Path) -> Result<Option<Self>> {
+        let content = match fs::read_to_string(path) {
+            Ok(v) => v,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read the config file {}", path.display()));
+            }
+        };
+
+        toml::de::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Failed to parse the config file {}", path.display()))
+    }
+
+    fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        for &(name, kind) in SETTINGS {
+            let var = env_var(name);
+            let Some(value) = env::var_os(&var) else {
+                continue;
+            };
+
+            let value = value
+                .into_string()
+                .ok()
+                .with_context(|| format!("The environment variable {var} isn't valid Unicode"))?;
+            let setting = parse_setting(name, kind, &value)
+                .with_context(|| format!("Invalid value of the environment variable {var}"))?;
+            config.merge(setting);
+        }
+
+        Ok(config)
+    }
+
+    // Settings of `other` replace the ones of `self`.
+    fn merge(&mut self, other: Self) {
+        self.no_editor = other.no_editor.or(self.no_editor);
+        self.edit_cmd = other.edit_cmd.or(self.edit_cmd.take());
+        self.manual_run = other.manual_run.or(self.manual_run);
+        self.timeout = other.timeout.or(self.timeout);
+        self.output_limit = other.output_limit.or(self.output_limit);
+        self.parallelism = other.parallelism.or(self.parallelism);
+        self.no_cache = other.no_cache.or(self.no_cache);
+        self.track = other.track.or(self.track.take());
+        self.theme = other.theme.or(self.theme);
+        self.keymap.watch.extend(other.keymap.watch);
+        self.keymap.list.extend(other.keymap.list);
+    }
+
+    // The value of a setting as a TOML value. `None` if it isn't set.
+    fn value(&self, name: &str) -> Option<String> {
+        match name {
+            "no-editor" => self.no_editor.map(|v| v.to_string()),
+            "edit-cmd" => self.edit_cmd.as_deref().map(toml_string),
+            "manual-run" => self.manual_run.map(|v| v.to_string()),
+            "timeout" => self.timeout.map(|v| v.to_string()),
+            "output-limit" => self.output_limit.map(|v| v.to_string()),
+            "parallelism" => self.parallelism.map(|v| v.to_string()),
+            "no-cache" => self.no_cache.map(|v| v.to_string()),
+            "track" => self.track.as_deref().map(toml_string),
+            "theme" => self.theme.and_then(|theme| {
+                theme
+                    .to_possible_value()
+                    .map(|value| toml_string(value.get_name()))
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Source {
+    User,
+    Project,
+    Env,
+    Cli,
+}
+
+// Where a setting was set.
+struct SourceDisplay<'a>(Source, &'a str);
+
+impl fmt::Display for SourceDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Source::User => f.write_str("user config"),
+            Source::Project => f.write_str("project config"),
+            Source::Env => write!(f, "environment variable {}", env_var(self.1)),
+            Source::Cli => f.write_str("command line"),
+        }
+    }
+}
+
+// A boolean setting from a flag and its negation. `None` if neither was passed.
+fn flag(set: bool, unset: bool) -> Option<bool> {
+    if set {
+        Some(true)
+    } else if unset {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+// `$XDG_CONFIG_HOME` or `~/.config` (`%APPDATA%` on Windows).
+fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(PathBuf::from);
+    }
+
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+}
+
+/// The path of the user's config file. `None` if the config directory is unknown.
+pub fn user_config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("rustlings").join("config.toml"))
+}
+
+fn env_var(name: &str) -> String {
+    let mut var = String::with_capacity(10 + name.len());
+    var.push_str("RUSTLINGS_");
+    var.extend(name.chars().map(|c| {
+        if c == '-' {
+            '_'
+        } else {
+            c.to_ascii_uppercase()
+        }
+    }));
+    var
+}
+
+fn toml_string(value: &str) -> String {
+    let mut string = String::with_capacity(value.len() + 2);
+    string.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => string.push_str("\\\""),
+            '\\' => string.push_str("\\\\"),
+            '\n' => string.push_str("\\n"),
+            '\t' => string.push_str("\\t"),
+            c => string.push(c),
+        }
+    }
+    string.push('"');
+    string
+}
+
+fn key_list_value(key_list: &KeyList) -> String {
+    match key_list {
+        KeyList::Single(key) => toml_string(key),
+        KeyList::Multiple(keys) => {
+            let keys = keys.iter().map(|key| toml_string(key)).collect::<Vec<_>>();
+            format!("[{}]", keys.join(", "))
+        }
+    }
+}
+
+// Convert a value from the command line or an environment variable to a TOML value.
+fn toml_value(kind: Kind, value: &str) -> Result<String> {
+    match kind {
+        Kind::Bool => match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(String::from("true")),
+            "false" | "0" | "no" | "off" => Ok(String::from("false")),
+            _ => bail!("Expected `true` or `false` but got `{value}`"),
+        },
+        Kind::Integer => {
+            let value = value.trim();
+            value
+                .parse::<u64>()
+                .with_context(|| format!("Expected a non-negative integer but got `{value}`"))?;
+            Ok(String::from(value))
+        }
+        Kind::String => Ok(toml_string(value)),
+    }
+}
+
+fn parse_setting(name: &str, kind: Kind, value: &str) -> Result<Config> {
+    let line = format!("{name} = {}", toml_value(kind, value)?);
+    toml::de::from_str(&line).map_err(|e| anyhow::Error::msg(e.message().to_string()))
+}
+
+fn setting_kind(name: &str) -> Result<Kind> {
+    if name.starts_with("keymap") {
+        bail!(
+            "Key bindings can't be set with this command. Edit the `[keymap]` tables of the config file instead"
+        );
+    }
+
+    SETTINGS
+        .iter()
+        .find(|(setting, _)| *setting == name)
+        .map(|(_, kind)| *kind)
+        .with_context(|| {
+            let available = SETTINGS
+                .iter()
+                .map(|(setting, _)| *setting)
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("Unknown setting `{name}`. The available settings: {available}")
+        })
+}
+
+// The config layers in the order of increasing precedence.
+fn layers(cli: Config) -> Result<Vec<(Source, Config)>> {
+    let mut layers = Vec::with_capacity(4);
+    if let Some(path) = user_config_path()
+        && let Some(config) = Config::read(&path)?
+    {
+        layers.push((Source::User, config));
+    }
+    if let Some(config) = Config::read(Path::new(PROJECT_CONFIG_PATH))? {
+        layers.push((Source::Project, config));
+    }
+    layers.push((Source::Env, Config::from_env()?));
+    layers.push((Source::Cli, cli));
+
+    Ok(layers)
+}
+
+/// Merge the user's config file, the project's config file, the environment variables and the
+/// command line arguments in the order of increasing precedence.
+pub fn load(cli: Config) -> Result<Config> {
+    let mut config = Config::default();
+    for (_, layer) in layers(cli)? {
+        config.merge(layer);
+    }
+
+    Ok(config)
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Show the config files and the effective settings with where they were set
+    Show,
+    /// Set a setting in the user's config file
+    Set {
+        /// The name of the setting like `edit-cmd`
+        name: String,
+        value: String,
+        /// Change the project's config file next to `info.toml` instead
+        #[arg(long)]
+        project: bool,
+    },
+    /// Remove a setting from the user's config file
+    Unset {
+        /// The name of the setting like `edit-cmd`
+        name: String,
+        /// Change the project's config file next to `info.toml` instead
+        #[arg(long)]
+        project: bool,
+    },
+}
+
+impl ConfigCommand {
+    pub fn run(self, cli: Config) -> Result<()> {
+        match self {
+            Self::Show => show(cli),
+            Self::Set {
+                name,
+                value,
+                project,
+            } => {
+                let kind = setting_kind(&name)?;
+                let value = toml_value(kind, &value)?;
+                edit(&config_path(project)?, &name, Some(&value))
+            }
+            Self::Unset { name, project } => {
+                setting_kind(&name)?;
+                edit(&config_path(project)?, &name, None)
+            }
+        }
+    }
+}
+
+fn config_path(project: bool) -> Result<PathBuf> {
+    if project {
+        return Ok(PathBuf::from(PROJECT_CONFIG_PATH));
+    }
+
+    user_config_path().context(
+        "Failed to determine the config directory. Set the environment variable `XDG_CONFIG_HOME`",
+    )
+}
+
+fn mode_bindings<'a>(config: &'a Config, mode: &str) -> &'a HashMap<String, KeyList> {
+    if mode == "watch" {
+        &config.keymap.watch
+    } else {
+        &config.keymap.list
+    }
+}
+
+fn show(cli: Config) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(b"Config files in the order of increasing precedence:\n")?;
+    let paths = [user_config_path(), Some(PathBuf::from(PROJECT_CONFIG_PATH))];
+    for (label, path) in ["user", "project"].into_iter().zip(paths) {
+        match path {
+            Some(path) if path.exists() => writeln!(stdout, "  {label}: {}", path.display())?,
+            Some(path) => writeln!(stdout, "  {label}: {} (not found)", path.display())?,
+            None => writeln!(stdout, "  {label}: unknown config directory")?,
+        }
+    }
+    stdout.write_all(b"Environment variables `RUSTLINGS_<NAME>` and command line arguments have a higher precedence\n\n")?;
+
+    let layers = layers(cli)?;
+    let mut lines = Vec::with_capacity(SETTINGS.len());
+    for &(name, _) in SETTINGS {
+        if let Some((source, value)) = layers
+            .iter()
+            .rev()
+            .find_map(|(source, config)| Some((*source, config.value(name)?)))
+        {
+            lines.push((format!("{name} = {value}"), SourceDisplay(source, name)));
+        }
+    }
+    for mode in ["watch", "list"] {
+        let mut names = layers
+            .iter()
+            .flat_map(|(_, config)| mode_bindings(config, mode).keys())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+
+        for name in names {
+            if let Some((source, key_list)) = layers.iter().rev().find_map(|(source, config)| {
+                Some((*source, mode_bindings(config, mode).get(name)?))
+            }) {
+                lines.push((
+                    format!("keymap.{mode}.{name} = {}", key_list_value(key_list)),
+                    SourceDisplay(source, ""),
+                ));
+            }
+        }
+    }
+
+    if lines.is_empty() {
+        stdout.write_all(b"No settings are set\n")?;
+        return Ok(());
+    }
+
+    let width = lines
+        .iter()
+        .map(|(line, _)| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (line, source) in lines {
+        writeln!(stdout, "{line:width$}  # {source}")?;
+    }
+
+    Ok(())
+}
+
+// Whether the line sets the top-level key `name`.
+fn sets_key(line: &str, name: &str) -> bool {
+    line.trim_start()
+        .strip_prefix(name)
+        .is_some_and(|rest| rest.trim_start().starts_with('='))
+}
+
+// Set (`Some`) or remove (`None`) a top-level key while keeping the rest of the content.
+// Returns `None` if the key to remove isn't set.
+fn edit_content(content: &str, name: &str, value: Option<&str>) -> Option<String> {
+    let mut lines = content.lines().collect::<Vec<_>>();
+    // Top-level keys must come before the first table.
+    let first_table = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    let key_line = lines[..first_table]
+        .iter()
+        .position(|line| sets_key(line, name));
+
+    let new_line = value.map(|value| format!("{name} = {value}"));
+    match (key_line, &new_line) {
+        (Some(ind), Some(new_line)) => lines[ind] = new_line,
+        (Some(ind), None) => {
+            lines.remove(ind);
+        }
+        (None, Some(new_line)) => {
+            lines.insert(first_table, new_line);
+            if first_table < lines.len() - 1 {
+                lines.insert(first_table + 1, "");
+            }
+        }
+        (None, None) => return None,
+    }
+
+    let mut new_content = lines.join("\n");
+    new_content.push('\n');
+    Some(new_content)
+}
+
+fn edit(path: &Path, name: &str, value: Option<&str>) -> Result<()> {
+    let content = match fs::read_to_string(path) {
+        Ok(v) => v,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("Failed to read the config file {}", path.display()));
+        }
+    };
+
+    let Some(new_content) = edit_content(&content, name, value) else {
+        println!("`{name}` isn't set in the config file {}", path.display());
+        return Ok(());
+    };
+    toml::de::from_str::<Config>(&new_content).with_context(|| {
+        format!(
+            "The config file {} would be invalid after the change",
+            path.display(),
+        )
+    })?;
+
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create the directory {}", dir.display()))?;
+    }
+    fs::write(path, new_content)
+        .with_context(|| format!("Failed to write the config file {}", path.display()))?;
+
+    match value {
+        Some(value) => println!("Set `{name} = {value}` in {}", path.display()),
+        None => println!("Removed `{name}` from {}", path.display()),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_top_level_keys() {
+        let content = "# My config\ntimeout = 5\n\n[keymap.watch]\nnext = \"N\"\n";
+        assert_eq!(
+            edit_content(content, "timeout", Some("3")).unwrap(),
+            "# My config\ntimeout = 3\n\n[keymap.watch]\nnext = \"N\"\n",
+        );
+        assert_eq!(
+            edit_content(content, "manual-run", Some("true")).unwrap(),
+            "# My config\ntimeout = 5\n\nmanual-run = true\n\n[keymap.watch]\nnext = \"N\"\n",
+        );
+        assert_eq!(
+            edit_content(content, "timeout", None).unwrap(),
+            "# My config\n\n[keymap.watch]\nnext = \"N\"\n",
+        );
+        // Only top-level keys are edited.
+        assert_eq!(edit_content(content, "next", None), None);
+        assert_eq!(
+            edit_content("", "track", Some("\"x\"")).unwrap(),
+            "track = \"x\"\n"
+        );
+    }
+
+    #[test]
+    fn setting_values() {
+        let config = parse_setting("edit-cmd", Kind::String, r#"code -r "a\b""#).unwrap();
+        assert_eq!(config.edit_cmd.as_deref(), Some(r#"code -r "a\b""#));
+        assert_eq!(
+            parse_setting("no-editor", Kind::Bool, "1")
+                .unwrap()
+                .no_editor,
+            Some(true),
+        );
+        assert!(parse_setting("timeout", Kind::Integer, "-1").is_err());
+        assert!(parse_setting("theme", Kind::String, "xml").is_err());
+        let config = parse_setting("theme", Kind::String, "high-contrast").unwrap();
+        assert!(config.theme == Some(Theme::HighContrast));
+        assert_eq!(config.value("theme").as_deref(), Some("\"high-contrast\""));
+        assert_eq!(env_var("output-limit"), "RUSTLINGS_OUTPUT_LIMIT");
+    }
+
+    #[test]
+    fn precedence() {
+        let mut config = parse_setting("timeout", Kind::Integer, "5").unwrap();
+        config.merge(parse_setting("manual-run", Kind::Bool, "true").unwrap());
+        config.merge(parse_setting("timeout", Kind::Integer, "7").unwrap());
+        assert_eq!(config.timeout, Some(7));
+        assert_eq!(config.manual_run, Some(true));
+        assert_eq!(config.value("timeout").as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn negated_flags() {
+        use clap::Parser;
+
+        let args = Args::try_parse_from([
+            "rustlings",
+            "--no-editor",
+            "--editor",
+            "--parallelism",
+            "2",
+            "--no-cache",
+        ])
+        .unwrap();
+        let config = Config::from_args(&args);
+        assert_eq!(config.no_editor, Some(false));
+        assert_eq!(config.manual_run, None);
+        assert_eq!(config.no_cache, Some(true));
+        assert_eq!(config.parallelism, Some(2));
+    }
+}
@@ -14,8 +14,9 @@ use crate::{
     cargo_toml::{BINS_BUFFER_CAPACITY, append_bins, bins_start_end_ind},
     cmd::CmdRunner,
     exercise::{OUTPUT_CAPACITY, RunnableExercise},
-    info_file::{ExerciseInfo, InfoFile},
+    info_file::{self, ALL_TRACKS, ExerciseInfo, InfoFile},
     term::ProgressCounter,
+    theme,
 };
 
 const MAX_N_EXERCISES: usize = 999;
@@ -85,9 +86,20 @@ fn check_info_file_exercises(info_file: &InfoFile) -> Result<HashSet<PathBuf>> {
             }
         }
 
-        if exercise_info.hint.trim_ascii().is_empty() {
+        let hint_levels = exercise_info.hint.levels();
+        if let Some(empty_ind) = hint_levels
+            .iter()
+            .position(|hint| hint.trim_ascii().is_empty())
+        {
+            if hint_levels.len() == 1 {
+                bail!(
+                    "The exercise `{name}` has an empty hint. Please provide a hint or at least tell the user why a hint isn't needed for this exercise"
+                );
+            }
+
             bail!(
-                "The exercise `{name}` has an empty hint. Please provide a hint or at least tell the user why a hint isn't needed for this exercise"
+                "The hint level {} of the exercise `{name}` is empty. Please provide a hint or remove the level",
+                empty_ind + 1,
             );
         }
 
@@ -139,6 +151,114 @@ fn check_info_file_exercises(info_file: &InfoFile) -> Result<HashSet<PathBuf>> {
     Ok(paths)
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    NotVisited,
+    OnPath,
+    Done,
+}
+
+// Depth-first search for a cycle in the prerequisites with the exercises on the current path in `path`.
+// Returns the index of the exercise where the found cycle starts.
+fn find_cycle(
+    exercise_ind: usize,
+    prerequisites: &[Vec<usize>],
+    visits: &mut [Visit],
+    path: &mut Vec<usize>,
+) -> Option<usize> {
+    match visits[exercise_ind] {
+        Visit::Done => return None,
+        Visit::OnPath => return Some(exercise_ind),
+        Visit::NotVisited => (),
+    }
+
+    visits[exercise_ind] = Visit::OnPath;
+    path.push(exercise_ind);
+    for &required_ind in &prerequisites[exercise_ind] {
+        if let Some(cycle_start) = find_cycle(required_ind, prerequisites, visits, path) {
+            return Some(cycle_start);
+        }
+    }
+    path.pop();
+    visits[exercise_ind] = Visit::Done;
+
+    None
+}
+
+// Check that the `requires` entries are unambiguous and known, and that there are no cycles.
+fn check_prerequisites(info_file: &InfoFile) -> Result<()> {
+    let exercise_infos = &info_file.exercises;
+
+    for exercise_info in exercise_infos {
+        for &required in &exercise_info.requires {
+            if exercise_infos
+                .iter()
+                .any(|exercise_info| exercise_info.dir == Some(required))
+                && exercise_infos
+                    .iter()
+                    .any(|exercise_info| exercise_info.name == required)
+            {
+                bail!(
+                    "The exercise `{}` requires `{required}` which is both an exercise and a directory. Rename one of them",
+                    exercise_info.name,
+                );
+            }
+        }
+    }
+
+    let prerequisites = info_file::prerequisites(exercise_infos)?;
+
+    let mut visits = vec![Visit::NotVisited; exercise_infos.len()];
+    let mut path = Vec::with_capacity(exercise_infos.len());
+
+    for exercise_ind in 0..exercise_infos.len() {
+        if let Some(cycle_start) = find_cycle(exercise_ind, &prerequisites, &mut visits, &mut path)
+        {
+            let cycle_start_ind = path.iter().position(|&ind| ind == cycle_start).unwrap();
+            let mut cycle = String::with_capacity(256);
+            for &ind in &path[cycle_start_ind..] {
+                cycle.push('`');
+                cycle.push_str(exercise_infos[ind].name);
+                cycle.push_str("` requires ");
+            }
+            cycle.push('`');
+            cycle.push_str(exercise_infos[cycle_start].name);
+            cycle.push('`');
+
+            bail!("Found a cycle in the `requires` entries of exercises: {cycle}");
+        }
+    }
+
+    Ok(())
+}
+
+// Check that the names of the tracks are unique and that they only contain known exercises.
+fn check_tracks(info_file: &InfoFile) -> Result<()> {
+    let mut names = HashSet::with_capacity(info_file.tracks.len());
+
+    for track in &info_file.tracks {
+        if track.name.is_empty() {
+            bail!("Found an empty track name in `info.toml`");
+        }
+        if track.name == ALL_TRACKS {
+            bail!("The track name `{ALL_TRACKS}` is reserved for selecting all exercises");
+        }
+        if !names.insert(track.name) {
+            bail!(
+                "The track name `{}` is duplicated. Track names must all be unique",
+                track.name,
+            );
+        }
+        if track.exercises.is_empty() {
+            bail!("The track `{}` doesn't contain any exercises", track.name);
+        }
+
+        track.contained_exercises(&info_file.exercises)?;
+    }
+
+    Ok(())
+}
+
 // Check `dir` for unexpected files.
 // Only Rust files in `allowed_rust_files` and `README.md` files are allowed.
 // Only one level of directory nesting is allowed.
@@ -213,7 +333,11 @@ fn check_exercises_unsolved(
 
             Some(
                 thread::Builder::new()
-                    .spawn(|| exercise_info.run_exercise(None, cmd_runner))
+                    .spawn(|| {
+                        exercise_info
+                            .run_exercise(None, cmd_runner)
+                            .map(|outcome| outcome.success())
+                    })
                     .map(|handle| (exercise_info.name, handle)),
             )
         })
@@ -262,6 +386,8 @@ fn check_exercises(info_file: &'static InfoFile, cmd_runner: &'static CmdRunner)
         .context("Failed to spawn a thread to check if any exercise is already solved")?;
 
     let info_file_paths = check_info_file_exercises(info_file)?;
+    check_prerequisites(info_file)?;
+    check_tracks(info_file)?;
     check_unexpected_files("exercises", &info_file_paths)?;
 
     handle.join().unwrap()
@@ -301,8 +427,8 @@ fn check_solutions(
 
                 let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
                 match exercise_info.run_solution(Some(&mut output), cmd_runner) {
-                    Ok(true) => SolutionCheck::Success { sol_path },
-                    Ok(false) => SolutionCheck::RunFailure { output },
+                    Ok(outcome) if outcome.success() => SolutionCheck::Success { sol_path },
+                    Ok(_) => SolutionCheck::RunFailure { output },
                     Err(e) => SolutionCheck::Err(e),
                 }
             })
@@ -317,7 +443,7 @@ fn check_solutions(
         .arg("--edition")
         .arg("2024")
         .arg("--color")
-        .arg("always")
+        .arg(theme::cargo_color())
         .stdin(Stdio::null());
 
     let mut progress_counter = ProgressCounter::new(&mut stdout, handles.len())?;
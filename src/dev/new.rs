@@ -82,6 +82,10 @@ pub const GITIGNORE: &[u8] = b"Cargo.lock
 target/
 .vscode/
 !.vscode/extensions.json
+.rustlings/
+.rustlings-state.txt.bak
+.rustlings-state.txt.tmp
+.rustlings-state.txt.lock
 ";
 
 const INFO_FILE_BEFORE_FORMAT_VERSION: &str =
@@ -121,8 +125,26 @@ name = "???"
 # the exercise as done when there are no warnings left.
 # strict_clippy = false
 
+# Exercises are done in the order of this file.
+# You can optionally list exercises or directories whose exercises have to be done first.
+# Rustlings continues with other exercises until these prerequisites are done.
+# requires = ["???"]
+
+# Rustlings kills the exercise's binary and tests if they run longer than `timeout` seconds.
+# You can optionally increase it for slow exercises (the default is `10`, `0` disables the limit).
+# timeout = 10
+
 # A multi-line hint to be shown to users on request.
+# It can also be a list of hints which are revealed one at a time:
+# hint = ["""???""", """???"""]
 hint = """???"""
+
+# Optional tracks which users can select with `rustlings --track NAME` to only work on a subset
+# of the exercises. Repeat this section for every track.
+# [[tracks]]
+# name = "???"
+# Names of exercises or directories.
+# exercises = ["???"]
 "#;
 
 const CARGO_TOML: &[u8] =
@@ -0,0 +1,292 @@
+use crossterm::{
+    QueueableCommand,
+    style::{Attribute, ResetColor, SetAttribute},
+};
+use serde::{Deserialize, Serialize};
+use std::io::{self, StdoutLock, Write};
+
+use crate::{
+    term::{self, CountedWrite, file_path, terminal_file_link},
+    theme::{Style, set_style, unset_style},
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct DiagnosticCode {
+    pub code: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct SpanLine {
+    pub text: String,
+    /// 1-based column (in chars) where the highlighting starts.
+    pub highlight_start: usize,
+    /// 1-based column (in chars) where the highlighting ends (exclusive).
+    pub highlight_end: usize,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Span {
+    pub file_name: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    pub is_primary: bool,
+    pub text: Vec<SpanLine>,
+    pub label: Option<String>,
+    pub suggested_replacement: Option<String>,
+}
+
+/// A compiler diagnostic as emitted by `rustc` and Clippy.
+#[derive(Deserialize, Serialize)]
+pub struct Diagnostic {
+    pub message: String,
+    pub code: Option<DiagnosticCode>,
+    /// `error`, `warning`, `note`, `help`, etc.
+    pub level: String,
+    pub spans: Vec<Span>,
+    pub children: Vec<Diagnostic>,
+    /// The diagnostic rendered by the compiler with ANSI colors.
+    #[serde(skip_serializing)]
+    pub rendered: Option<String>,
+}
+
+impl Diagnostic {
+    fn primary_span(&self) -> Option<&Span> {
+        self.spans.iter().find(|span| span.is_primary)
+    }
+
+    // Diagnostics like "aborting due to 2 previous errors" or
+    // "For more information about this error, try `rustc --explain E0308`" only repeat
+    // information which is already shown.
+    fn is_summary(&self) -> bool {
+        self.spans.is_empty()
+            && self.code.is_none()
+            && (self.level == "failure-note" || self.message.starts_with("aborting due to"))
+    }
+
+    // The same diagnostic is reported again by every stage compiling the exercise.
+    fn is_duplicate_of(&self, other: &Self) -> bool {
+        self.level == other.level
+            && self.message == other.message
+            && self.code.as_ref().map(|code| &code.code)
+                == other.code.as_ref().map(|code| &code.code)
+            && match (self.primary_span(), other.primary_span()) {
+                (Some(span), Some(other_span)) => {
+                    span.file_name == other_span.file_name
+                        && span.line_start == other_span.line_start
+                        && span.column_start == other_span.column_start
+                }
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+/// Append a diagnostic unless it only summarizes others or is already contained.
+/// Returns whether it was appended.
+pub fn push_unique(diagnostics: &mut Vec<Diagnostic>, diagnostic: Diagnostic) -> bool {
+    if diagnostic.is_summary()
+        || diagnostics
+            .iter()
+            .any(|other| diagnostic.is_duplicate_of(other))
+    {
+        return false;
+    }
+
+    diagnostics.push(diagnostic);
+    true
+}
+
+fn level_style(level: &str) -> Style {
+    match level {
+        "error" | "error: internal compiler error" => Style::Failure,
+        "warning" => Style::Warning,
+        _ => Style::Info,
+    }
+}
+
+fn write_count(stdout: &mut StdoutLock, count: usize, singular: &str) -> io::Result<()> {
+    write!(stdout, "{count} {singular}")?;
+    if count != 1 {
+        stdout.write_all(b"s")?;
+    }
+
+    Ok(())
+}
+
+// The header line like `2 errors, 1 warning`.
+fn render_header(stdout: &mut StdoutLock, diagnostics: &[Diagnostic]) -> io::Result<()> {
+    let n_errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level.starts_with("error"))
+        .count();
+    let n_warnings = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level == "warning")
+        .count();
+    let n_other = diagnostics.len() - n_errors - n_warnings;
+
+    let mut separator = false;
+    for (count, singular, style) in [
+        (n_errors, "error", Style::Failure),
+        (n_warnings, "warning", Style::Warning),
+        (n_other, "note", Style::Info),
+    ] {
+        if count == 0 {
+            continue;
+        }
+
+        if separator {
+            stdout.write_all(b", ")?;
+        }
+        separator = true;
+
+        // Set for every count since unsetting a style can reset the intensity.
+        stdout.queue(SetAttribute(Attribute::Bold))?;
+        set_style(stdout, style)?;
+        write_count(stdout, count, singular)?;
+        unset_style(stdout, style)?;
+    }
+    stdout.queue(ResetColor)?;
+
+    stdout.write_all(b"\n\n")
+}
+
+fn render_location(stdout: &mut StdoutLock, span: &Span, emit_file_links: bool) -> io::Result<()> {
+    stdout.write_all(b"  --> ")?;
+    file_path(stdout, Style::Path, |writer| {
+        let location = format!(
+            "{}:{}:{}",
+            span.file_name, span.line_start, span.column_start
+        );
+        if emit_file_links && let Some(canonical_path) = term::canonicalize(&span.file_name) {
+            terminal_file_link(writer, &location, &canonical_path)
+        } else {
+            writer.write_str(&location)
+        }
+    })?;
+    stdout.write_all(b"\n")
+}
+
+// Show the source lines of a span with the highlighted part underlined.
+fn render_span_lines(stdout: &mut StdoutLock, span: &Span, gutter_width: usize) -> io::Result<()> {
+    let style = if span.is_primary {
+        Style::Failure
+    } else {
+        Style::Path
+    };
+    let marker = if span.is_primary { b'^' } else { b'-' };
+
+    for (line_ind, line) in span.text.iter().enumerate() {
+        set_style(stdout, Style::Path)?;
+        write!(stdout, "{:>gutter_width$} | ", span.line_start + line_ind)?;
+        stdout.queue(ResetColor)?;
+        stdout.write_all(line.text.as_bytes())?;
+        stdout.write_all(b"\n")?;
+
+        set_style(stdout, Style::Path)?;
+        write!(stdout, "{:gutter_width$} | ", "")?;
+        stdout.queue(ResetColor)?;
+        set_style(stdout, style)?;
+        for _ in 1..line.highlight_start {
+            stdout.write_all(b" ")?;
+        }
+        for _ in line.highlight_start..line.highlight_end.max(line.highlight_start + 1) {
+            stdout.write_all(&[marker])?;
+        }
+
+        // Show the label after the last line of the span.
+        if line_ind + 1 == span.text.len()
+            && let Some(label) = &span.label
+        {
+            stdout.write_all(b" ")?;
+            stdout.write_all(label.as_bytes())?;
+        }
+        stdout.queue(ResetColor)?;
+        stdout.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+fn render_diagnostic(
+    stdout: &mut StdoutLock,
+    diagnostic: &Diagnostic,
+    emit_file_links: bool,
+) -> io::Result<()> {
+    let style = level_style(&diagnostic.level);
+    stdout.queue(SetAttribute(Attribute::Bold))?;
+    set_style(stdout, style)?;
+    stdout.write_all(diagnostic.level.as_bytes())?;
+    if let Some(code) = &diagnostic.code {
+        write!(stdout, "[{}]", code.code)?;
+    }
+    unset_style(stdout, style)?;
+    stdout.queue(SetAttribute(Attribute::Bold))?;
+    stdout.write_all(b": ")?;
+    stdout.write_all(diagnostic.message.as_bytes())?;
+    stdout.queue(ResetColor)?;
+    stdout.write_all(b"\n")?;
+
+    if let Some(primary_span) = diagnostic.primary_span() {
+        render_location(stdout, primary_span, emit_file_links)?;
+    }
+
+    let gutter_width = diagnostic
+        .spans
+        .iter()
+        .map(|span| (span.line_start + span.text.len()).to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max(2);
+    for span in &diagnostic.spans {
+        render_span_lines(stdout, span, gutter_width)?;
+    }
+
+    for child in &diagnostic.children {
+        write!(stdout, "{:gutter_width$} = ", "")?;
+        stdout.queue(SetAttribute(Attribute::Bold))?;
+        set_style(stdout, level_style(&child.level))?;
+        stdout.write_all(child.level.as_bytes())?;
+        stdout.queue(ResetColor)?;
+        stdout.write_all(b": ")?;
+        stdout.write_all(child.message.as_bytes())?;
+
+        let mut suggestions = child
+            .spans
+            .iter()
+            .filter_map(|span| span.suggested_replacement.as_deref());
+        if let Some(suggestion) = suggestions.next() {
+            stdout.write_all(b": ")?;
+            set_style(stdout, Style::Success)?;
+            write!(stdout, "`{suggestion}`")?;
+            for suggestion in suggestions {
+                write!(stdout, ", `{suggestion}`")?;
+            }
+            stdout.queue(ResetColor)?;
+        }
+
+        stdout.write_all(b"\n")?;
+    }
+
+    stdout.write_all(b"\n")
+}
+
+/// Render the diagnostics with a header counting them.
+pub fn render_diagnostics(
+    stdout: &mut StdoutLock,
+    diagnostics: &[Diagnostic],
+    emit_file_links: bool,
+) -> io::Result<()> {
+    if diagnostics.is_empty() {
+        return Ok(());
+    }
+
+    render_header(stdout, diagnostics)?;
+    for diagnostic in diagnostics {
+        render_diagnostic(stdout, diagnostic, emit_file_links)?;
+    }
+
+    Ok(())
+}
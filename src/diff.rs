@@ -0,0 +1,331 @@
+use anyhow::{Context, Result};
+use crossterm::{
+    QueueableCommand,
+    style::{Attribute, ResetColor, SetAttribute},
+};
+use std::{
+    fs,
+    io::{self, Write},
+};
+
+use crate::theme::{Style, set_style};
+
+// The number of unchanged lines shown around changes.
+const CONTEXT: usize = 2;
+
+struct Line<'a> {
+    // 1-based.
+    number: usize,
+    text: &'a str,
+    // Without whitespace and comments outside of literals.
+    normalized: String,
+}
+
+enum Op {
+    // Indices of the old line and the new line.
+    Equal(usize, usize),
+    // Only in the old content.
+    Delete(usize),
+    // Only in the new content.
+    Insert(usize),
+}
+
+// Remove whitespace and comments outside of string and char literals.
+// Strings spanning multiple lines and raw strings aren't handled specially.
+fn normalize(line: &str, in_block_comment: &mut bool) -> String {
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut normalized = String::with_capacity(line.len());
+    let mut in_string = false;
+
+    let mut ind = 0;
+    while let Some(&c) = chars.get(ind) {
+        ind += 1;
+        let next = chars.get(ind).copied();
+
+        if *in_block_comment {
+            if c == '*' && next == Some('/') {
+                ind += 1;
+                *in_block_comment = false;
+            }
+            continue;
+        }
+
+        if in_string {
+            normalized.push(c);
+            if c == '\\' {
+                if let Some(escaped) = next {
+                    normalized.push(escaped);
+                    ind += 1;
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '/' if next == Some('/') => break,
+            '/' if next == Some('*') => {
+                ind += 1;
+                *in_block_comment = true;
+            }
+            '"' => {
+                normalized.push(c);
+                in_string = true;
+            }
+            '\'' => {
+                // A char literal like `'"'` or `'\''`. Otherwise, a lifetime.
+                let end = if next == Some('\\') {
+                    chars[ind + 1..]
+                        .iter()
+                        .skip(1)
+                        .position(|&c| c == '\'')
+                        .map(|pos| ind + 2 + pos)
+                } else if chars.get(ind + 1) == Some(&'\'') {
+                    Some(ind + 1)
+                } else {
+                    None
+                };
+
+                normalized.push(c);
+                if let Some(end) = end {
+                    normalized.extend(&chars[ind..=end]);
+                    ind = end + 1;
+                }
+            }
+            c if c.is_whitespace() => (),
+            c => normalized.push(c),
+        }
+    }
+
+    normalized
+}
+
+// If whitespace and comments are ignored, lines with only whitespace or comments are skipped.
+fn lines(content: &str, ignore_whitespace_and_comments: bool) -> Vec<Line<'_>> {
+    let mut in_block_comment = false;
+
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(ind, text)| {
+            let normalized = if ignore_whitespace_and_comments {
+                let normalized = normalize(text, &mut in_block_comment);
+                if normalized.is_empty() {
+                    return None;
+                }
+                normalized
+            } else {
+                text.trim_end().to_string()
+            };
+
+            Some(Line {
+                number: ind + 1,
+                text: text.trim_end(),
+                normalized,
+            })
+        })
+        .collect()
+}
+
+// Based on the longest common subsequence. Deletions come before insertions.
+fn ops(old: &[Line], new: &[Line]) -> Vec<Op> {
+    let width = new.len() + 1;
+    // The length of the longest common subsequence of `old[i..]` and `new[j..]`
+    // at the index `i * width + j`.
+    let mut lcs = vec![0_u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i].normalized == new[j].normalized {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i].normalized == new[j].normalized {
+            ops.push(Op::Equal(i, j));
+            i += 1;
+            j += 1;
+        } else if j == new.len()
+            || (i < old.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+        {
+            ops.push(Op::Delete(i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(j));
+            j += 1;
+        }
+    }
+
+    ops
+}
+
+fn write_hunk(writer: &mut impl Write, old: &[Line], new: &[Line], ops: &[Op]) -> io::Result<()> {
+    let old_line = ops.iter().find_map(|op| match op {
+        Op::Equal(i, _) | Op::Delete(i) => Some(old[*i].number),
+        Op::Insert(_) => None,
+    });
+    let new_line = ops.iter().find_map(|op| match op {
+        Op::Equal(_, j) | Op::Insert(j) => Some(new[*j].number),
+        Op::Delete(_) => None,
+    });
+
+    set_style(writer, Style::Info)?;
+    writeln!(
+        writer,
+        "@@ -{} +{} @@",
+        old_line.unwrap_or(0),
+        new_line.unwrap_or(0),
+    )?;
+    writer.queue(ResetColor)?;
+
+    for op in ops {
+        match op {
+            Op::Equal(i, _) => {
+                writer.write_all(b" ")?;
+                writer.write_all(old[*i].text.as_bytes())?;
+            }
+            Op::Delete(i) => {
+                set_style(writer, Style::Failure)?;
+                writer.write_all(b"-")?;
+                writer.write_all(old[*i].text.as_bytes())?;
+                writer.queue(ResetColor)?;
+            }
+            Op::Insert(j) => {
+                set_style(writer, Style::Success)?;
+                writer.write_all(b"+")?;
+                writer.write_all(new[*j].text.as_bytes())?;
+                writer.queue(ResetColor)?;
+            }
+        }
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+/// Write a colored unified diff from `old` to `new` with the labels in its header.
+/// Nothing is written if there are no differences. Returns whether there are differences.
+pub fn write_diff(
+    writer: &mut impl Write,
+    old_label: &str,
+    old: &str,
+    new_label: &str,
+    new: &str,
+    ignore_whitespace_and_comments: bool,
+) -> io::Result<bool> {
+    let old = lines(old, ignore_whitespace_and_comments);
+    let new = lines(new, ignore_whitespace_and_comments);
+    let ops = ops(&old, &new);
+    let is_change = |op: &Op| !matches!(op, Op::Equal(..));
+
+    let Some(mut first_change) = ops.iter().position(is_change) else {
+        return Ok(false);
+    };
+
+    writer.queue(SetAttribute(Attribute::Bold))?;
+    writeln!(writer, "--- {old_label}")?;
+    writeln!(writer, "+++ {new_label}")?;
+    writer.queue(ResetColor)?;
+
+    loop {
+        // Merge changes which are separated by only a few unchanged lines.
+        let mut last_change = first_change;
+        while let Some(next_change) = ops[last_change + 1..].iter().position(is_change)
+            && next_change <= 2 * CONTEXT
+        {
+            last_change += 1 + next_change;
+        }
+
+        let end = (last_change + 1 + CONTEXT).min(ops.len());
+        write_hunk(
+            writer,
+            &old,
+            &new,
+            &ops[first_change.saturating_sub(CONTEXT)..end],
+        )?;
+
+        let Some(next_change) = ops[end..].iter().position(is_change) else {
+            return Ok(true);
+        };
+        first_change = end + next_change;
+    }
+}
+
+/// Read an exercise and its solution and write their diff ignoring whitespace and comments.
+pub fn diff_with_solution(
+    writer: &mut impl Write,
+    exercise_path: &str,
+    solution_path: &str,
+) -> Result<()> {
+    let exercise = fs::read_to_string(exercise_path)
+        .with_context(|| format!("Failed to read the exercise file {exercise_path}"))?;
+    let solution = fs::read_to_string(solution_path)
+        .with_context(|| format!("Failed to read the solution file {solution_path}"))?;
+
+    let different = write_diff(
+        writer,
+        &format!("{exercise_path} (your exercise)"),
+        &exercise,
+        &format!("{solution_path} (solution)"),
+        &solution,
+        true,
+    )
+    .context("Failed to write the diff")?;
+    if !different {
+        writer.write_all(b"No differences apart from whitespace and comments\n")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::term::strip_ansi;
+
+    #[test]
+    fn normalize_line() {
+        let mut in_block_comment = false;
+        let mut normalize = |line| normalize(line, &mut in_block_comment);
+
+        assert_eq!(normalize("let x = 1; // TODO"), "letx=1;");
+        assert_eq!(normalize(r#"let s = "a // b";"#), r#"lets="a // b";"#);
+        assert_eq!(normalize(r#"let c = '"'; // x"#), r#"letc='"';"#);
+        assert_eq!(normalize(r"let c = '\''; // x"), r"letc='\'';");
+        assert_eq!(normalize("fn f<'a>(x: &'a str) {}"), "fnf<'a>(x:&'astr){}");
+        assert_eq!(normalize("a /* b */ c /* d"), "ac");
+        assert_eq!(normalize("still a comment */ e"), "e");
+    }
+
+    #[test]
+    fn diff() {
+        let exercise = "fn main() {\n    // TODO: Fix\n    let x=1;\n    let y = 2;\n    println!(\"{x}\");\n}\n";
+        let solution = "fn main() {\n    let x = 1;\n    let y = 3;\n    println!(\"{x}\");\n}\n";
+
+        let mut output = Vec::new();
+        assert!(write_diff(&mut output, "e.rs", exercise, "s.rs", solution, true).unwrap());
+        assert_eq!(
+            strip_ansi(&output),
+            "--- e.rs\n+++ s.rs\n\
+             @@ -1 +1 @@\n \
+             fn main() {\n     \
+             let x=1;\n\
+             -    let y = 2;\n\
+             +    let y = 3;\n     \
+             println!(\"{x}\");\n \
+             }\n",
+        );
+
+        output.clear();
+        let commented = exercise.replace("let x=1;", "let x = 1; // Done");
+        assert!(!write_diff(&mut output, "e.rs", exercise, "c.rs", &commented, true).unwrap());
+        assert!(output.is_empty());
+        assert!(write_diff(&mut output, "e.rs", exercise, "c.rs", &commented, false).unwrap());
+    }
+}
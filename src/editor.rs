@@ -41,7 +41,8 @@ fn program_exists(program: &str) -> bool {
 
 pub enum Editor {
     Cmd(Cow<'static, str>, Vec<String>),
-    Zellij(Option<(String, u32, usize)>),
+    // The ID of the open pane and the path of the file open in it.
+    Zellij(Option<(String, u32, Cow<'static, str>)>),
 }
 
 impl Editor {
@@ -71,20 +72,16 @@ impl Editor {
         Ok(None)
     }
 
-    pub fn open(
-        mut self,
-        exercise_ind: usize,
-        exercise_path: &'static str,
-    ) -> Result<EditorJoinHandle> {
+    pub fn open(mut self, path: Cow<'static, str>) -> Result<EditorJoinHandle> {
         let handle = thread::Builder::new()
             .spawn(move || {
                 match &mut self {
                     Editor::Cmd(program, args) => {
-                        run_cmd(Command::new(&**program).args(args).arg(exercise_path))?;
+                        run_cmd(Command::new(&**program).args(args).arg(&*path))?;
                     }
                     Editor::Zellij(open_pane) => {
-                        if let Some((pane_id_str, pane_id, open_exercise_ind)) = open_pane {
-                            if *open_exercise_ind == exercise_ind {
+                        if let Some((pane_id_str, pane_id, open_path)) = open_pane {
+                            if *open_path == path {
                                 if zellij::pane_open(*pane_id)? {
                                     return Ok(self);
                                 }
@@ -93,17 +90,13 @@ impl Editor {
                             }
                         }
 
-                        let stdout = run_cmd(
-                            Command::new("zellij")
-                                .arg("action")
-                                .arg("edit")
-                                .arg(exercise_path),
-                        )?;
+                        let stdout =
+                            run_cmd(Command::new("zellij").arg("action").arg("edit").arg(&*path))?;
 
                         let (pane_id_str, pane_id) = zellij::parse_pane_id(&stdout)
                             .context("Failed to parse the ID of the new Zellij pane")?;
 
-                        *open_pane = Some((pane_id_str, pane_id, exercise_ind));
+                        *open_pane = Some((pane_id_str, pane_id, path));
                     }
                 }
 
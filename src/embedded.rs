@@ -95,6 +95,11 @@ impl EmbeddedFiles {
         Ok(())
     }
 
+    /// The original content of an exercise file.
+    pub fn exercise_content(&self, exercise_ind: usize) -> &'static [u8] {
+        self.exercise_files[exercise_ind].exercise
+    }
+
     pub fn write_exercise_to_disk(&self, exercise_ind: usize, path: &str) -> Result<()> {
         let exercise_files = &self.exercise_files[exercise_ind];
         let dir = &self.exercise_dirs[exercise_files.dir_ind];
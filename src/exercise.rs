@@ -1,13 +1,22 @@
 use anyhow::Result;
 use crossterm::{
     QueueableCommand,
-    style::{Attribute, Color, ResetColor, SetAttribute, SetForegroundColor},
+    style::{Attribute, ResetColor, SetAttribute},
+    terminal,
+};
+use serde::{Serialize, Serializer};
+use std::{
+    io::{self, StdoutLock, Write},
+    ops::Range,
+    time::{Duration, Instant},
 };
-use std::io::{self, StdoutLock, Write};
 
 use crate::{
-    cmd::CmdRunner,
+    cmd::{self, CmdExit, CmdRunner},
+    diagnostics::Diagnostic,
+    state_file::ExerciseHistory,
     term::{self, CountedWrite, file_path, terminal_file_link, write_ansi},
+    theme::{Style, set_style_ansi},
 };
 
 /// The initial capacity of the output buffer.
@@ -22,7 +31,7 @@ pub fn solution_link_line(
     stdout.write_all(b"Solution")?;
     stdout.queue(ResetColor)?;
     stdout.write_all(b" for comparison: ")?;
-    file_path(stdout, Color::Cyan, |writer| {
+    file_path(stdout, Style::Info, |writer| {
         if emit_file_links && let Some(canonical_path) = term::canonicalize(solution_path) {
             terminal_file_link(writer, solution_path, &canonical_path)
         } else {
@@ -32,36 +41,196 @@ pub fn solution_link_line(
     stdout.write_all(b"\n")
 }
 
+// Highlight the reason why a stage failed in the output.
+fn write_failure_msg(output: &mut Vec<u8>, msg: &str, stream: bool) -> io::Result<()> {
+    let start = output.len();
+    write_ansi(output, SetAttribute(Attribute::Bold));
+    set_style_ansi(output, Style::Failure);
+    output.extend_from_slice(msg.as_bytes());
+    write_ansi(output, ResetColor);
+    output.push(b'\n');
+
+    if stream {
+        cmd::print_streamed(&output[start..])?;
+    }
+
+    Ok(())
+}
+
 // Run an exercise binary and append its output to the `output` buffer.
 // Compilation must be done before calling this method.
 fn run_bin(
     bin_name: &str,
     mut output: Option<&mut Vec<u8>>,
     cmd_runner: &CmdRunner,
-) -> Result<bool> {
+    timeout: Option<Duration>,
+) -> Result<CmdExit> {
+    let stream = cmd_runner.streams_output();
+
     if let Some(output) = output.as_deref_mut() {
+        let start = output.len();
         write_ansi(output, SetAttribute(Attribute::Underlined));
         output.extend_from_slice(b"Output");
         write_ansi(output, ResetColor);
         output.push(b'\n');
+
+        if stream {
+            cmd::print_streamed(&output[start..])?;
+        }
     }
 
-    let success = cmd_runner.run_debug_bin(bin_name, output.as_deref_mut())?;
+    let exit = cmd_runner.run_debug_bin(bin_name, output.as_deref_mut(), timeout)?;
 
-    if let Some(output) = output
-        && !success
-    {
-        // This output is important to show the user that something went wrong.
-        // Otherwise, calling something like `exit(1)` in an exercise without further output
-        // leaves the user confused about why the exercise isn't done yet.
-        write_ansi(output, SetAttribute(Attribute::Bold));
-        write_ansi(output, SetForegroundColor(Color::Red));
-        output.extend_from_slice(b"The exercise didn't run successfully (nonzero exit code)");
-        write_ansi(output, ResetColor);
-        output.push(b'\n');
+    if let Some(output) = output {
+        match exit {
+            CmdExit::Status(status) if status.success() => (),
+            // This output is important to show the user that something went wrong.
+            // Otherwise, calling something like `exit(1)` in an exercise without further output
+            // leaves the user confused about why the exercise isn't done yet.
+            CmdExit::Status(_) => write_failure_msg(
+                output,
+                "The exercise didn't run successfully (nonzero exit code)",
+                stream,
+            )?,
+            CmdExit::TimedOut(timeout) => write_failure_msg(
+                output,
+                &format!("The exercise timed out after {}s", timeout.as_secs()),
+                stream,
+            )?,
+            // Reported by `RunOutcome::run_stage`.
+            CmdExit::Cancelled => (),
+        }
+    }
+
+    Ok(exit)
+}
+
+/// A stage of running an exercise.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Build,
+    Test,
+    Clippy,
+    Run,
+}
+
+impl Stage {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Build => "build",
+            Self::Test => "test",
+            Self::Clippy => "clippy",
+            Self::Run => "run",
+        }
+    }
+}
+
+// Serialized as its name to keep JSON documents consistent with the text output.
+impl Serialize for Stage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_millis())
+}
+
+#[derive(Serialize)]
+pub struct StageOutcome {
+    pub stage: Stage,
+    pub success: bool,
+    /// `None` if the process was terminated by a signal or timed out.
+    pub exit_code: Option<i32>,
+    /// The process was killed because it exceeded the time limit.
+    pub timed_out: bool,
+    /// The process was killed on the user's request.
+    pub cancelled: bool,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration,
+    /// The range of the stage's output in the output buffer.
+    /// `None` if the output wasn't captured or was discarded by a later stage.
+    #[serde(skip)]
+    pub output: Option<Range<usize>>,
+}
+
+impl StageOutcome {
+    /// The output of this stage if it is still in the output buffer.
+    pub fn output<'a>(&self, output: &'a [u8]) -> Option<&'a [u8]> {
+        output.get(self.output.clone()?)
+    }
+}
+
+/// The outcome of every stage reached while running an exercise (in order).
+pub struct RunOutcome {
+    pub stages: Vec<StageOutcome>,
+    /// Compiler diagnostics without duplicates if the command runner parses them.
+    pub diagnostics: Vec<Diagnostic>,
+    // The output was streamed to the terminal.
+    stream: bool,
+}
+
+impl RunOutcome {
+    // Run a stage, record its outcome and return `true` on success.
+    fn run_stage(
+        &mut self,
+        stage: Stage,
+        output: &mut Option<&mut Vec<u8>>,
+        run: impl FnOnce(Option<&mut Vec<u8>>, &mut Vec<Diagnostic>) -> Result<CmdExit>,
+    ) -> Result<bool> {
+        let output_start = output.as_deref().map_or(0, Vec::len);
+        let start = Instant::now();
+        let exit = run(output.as_deref_mut(), &mut self.diagnostics)?;
+        let duration = start.elapsed();
+
+        let success = exit.success();
+        self.stages.push(StageOutcome {
+            stage,
+            success,
+            exit_code: exit.code(),
+            timed_out: matches!(exit, CmdExit::TimedOut(_)),
+            cancelled: matches!(exit, CmdExit::Cancelled),
+            duration,
+            output: output.as_deref().map(|output| output_start..output.len()),
+        });
+
+        if let Some(output) = output
+            && matches!(exit, CmdExit::Cancelled)
+        {
+            write_failure_msg(output, "Cancelled", self.stream)?;
+        }
+
+        Ok(success)
     }
 
-    Ok(success)
+    // Clear the output buffer because its content will be shown again by a later stage.
+    // Streamed output is erased from the terminal.
+    fn discard_output(&mut self, output: &mut Option<&mut Vec<u8>>) -> io::Result<()> {
+        if let Some(output) = output {
+            if self.stream {
+                let term_width = terminal::size().map_or(u16::MAX, |size| size.0);
+                term::erase_streamed(&mut io::stdout().lock(), output, term_width)?;
+            }
+
+            output.clear();
+
+            for outcome in &mut self.stages {
+                outcome.output = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The run was cancelled. Its outcome says nothing about the exercise.
+    pub fn cancelled(&self) -> bool {
+        self.stages.last().is_some_and(|outcome| outcome.cancelled)
+    }
+
+    /// The exercise is done if all stages succeeded.
+    pub fn success(&self) -> bool {
+        !self.stages.is_empty() && self.stages.iter().all(|outcome| outcome.success)
+    }
 }
 
 /// See `info_file::ExerciseInfo`
@@ -73,17 +242,46 @@ pub struct Exercise {
     pub canonical_path: Option<String>,
     pub test: bool,
     pub strict_clippy: bool,
-    pub hint: &'static str,
+    pub timeout: Option<u64>,
+    /// The levels of the hint in the order of revealing them. Never empty.
+    pub hints: Vec<&'static str>,
+    /// Indices of the exercises which have to be done before this exercise.
+    pub prerequisites: Vec<usize>,
+    /// Part of the selected track. `true` for all exercises if no track is selected.
+    pub in_track: bool,
     pub done: bool,
+    /// Skipped by the user while pending. Only revisited after all other pending exercises.
+    pub skipped: bool,
+    pub history: ExerciseHistory,
 }
 
 impl Exercise {
+    /// The number of hint levels. At least 1.
+    pub fn n_hint_levels(&self) -> u32 {
+        self.hints.len() as u32
+    }
+
+    /// The hint level (starting at 1) to reveal next or the last one if all were revealed.
+    pub fn next_hint_level(&self) -> u32 {
+        (self.history.hint_level + 1).min(self.n_hint_levels())
+    }
+
+    /// A heading like `Hint` for a single hint or `Hint 2/3` for multiple levels.
+    pub fn write_hint_heading(&self, writer: &mut impl Write, level: u32) -> io::Result<()> {
+        writer.write_all(b"Hint")?;
+        if self.n_hint_levels() > 1 {
+            write!(writer, " {level}/{}", self.n_hint_levels())?;
+        }
+
+        Ok(())
+    }
+
     pub fn terminal_file_link<'a>(
         &self,
         writer: &mut impl CountedWrite<'a>,
         emit_file_links: bool,
     ) -> io::Result<()> {
-        file_path(writer, Color::Blue, |writer| {
+        file_path(writer, Style::Path, |writer| {
             if emit_file_links && let Some(canonical_path) = self.canonical_path.as_deref() {
                 terminal_file_link(writer, self.path, canonical_path)
             } else {
@@ -98,6 +296,7 @@ pub trait RunnableExercise {
     fn dir(&self) -> Option<&str>;
     fn strict_clippy(&self) -> bool;
     fn test(&self) -> bool;
+    fn timeout(&self) -> Option<u64>;
 
     // Compile, check and run the exercise or its solution (depending on `bin_name´).
     // The output is written to the `output` buffer after clearing it.
@@ -106,65 +305,100 @@ pub trait RunnableExercise {
         bin_name: &str,
         mut output: Option<&mut Vec<u8>>,
         cmd_runner: &CmdRunner,
-    ) -> Result<bool> {
+    ) -> Result<RunOutcome> {
         if let Some(output) = output.as_deref_mut() {
             output.clear();
         }
 
-        let build_success = cmd_runner
-            .cargo("build", bin_name, output.as_deref_mut())
-            .run("cargo build …")?;
+        let timeout = cmd_runner.timeout(self.timeout());
+        let mut outcome = RunOutcome {
+            stages: Vec::with_capacity(4),
+            diagnostics: Vec::new(),
+            stream: output.is_some() && cmd_runner.streams_output(),
+        };
+
+        let build_success =
+            outcome.run_stage(Stage::Build, &mut output, |output, diagnostics| {
+                let mut build_cmd = cmd_runner.cargo("build", bin_name, output);
+                build_cmd.diagnostics(diagnostics);
+                build_cmd.run("cargo build …")
+            })?;
         if !build_success {
-            return Ok(false);
+            return Ok(outcome);
         }
 
         // Discard the compiler output because it will be shown again by `cargo test` or Clippy.
-        if let Some(output) = output.as_deref_mut() {
-            output.clear();
-        }
+        outcome.discard_output(&mut output)?;
 
         if self.test() {
-            let output_is_some = output.is_some();
-            let mut test_cmd = cmd_runner.cargo("test", bin_name, output.as_deref_mut());
-            if output_is_some {
-                test_cmd.args(["--", "--color", "always", "--format", "pretty"]);
-            }
-            let test_success = test_cmd.run("cargo test …")?;
+            let test_success =
+                outcome.run_stage(Stage::Test, &mut output, |mut output, diagnostics| {
+                    let exit =
+                        cmd_runner.test(bin_name, output.as_deref_mut(), diagnostics, timeout)?;
+                    if let Some(output) = output
+                        && let CmdExit::TimedOut(timeout) = exit
+                    {
+                        write_failure_msg(
+                            output,
+                            &format!("The tests timed out after {}s", timeout.as_secs()),
+                            cmd_runner.streams_output(),
+                        )?;
+                    }
+                    Ok(exit)
+                })?;
             if !test_success {
-                run_bin(bin_name, output, cmd_runner)?;
-                return Ok(false);
+                if !outcome.cancelled() {
+                    outcome.run_stage(Stage::Run, &mut output, |output, _| {
+                        run_bin(bin_name, output, cmd_runner, timeout)
+                    })?;
+                }
+                return Ok(outcome);
             }
 
             // Discard the compiler output because it will be shown again by Clippy.
-            if let Some(output) = output.as_deref_mut() {
-                output.clear();
-            }
+            outcome.discard_output(&mut output)?;
         }
 
-        let mut clippy_cmd = cmd_runner.cargo("clippy", bin_name, output.as_deref_mut());
+        outcome.run_stage(Stage::Clippy, &mut output, |output, diagnostics| {
+            let mut clippy_cmd = cmd_runner.cargo("clippy", bin_name, output);
+            clippy_cmd.diagnostics(diagnostics);
 
-        // `--profile test` is required to also check code with `#[cfg(test)]`.
-        if FORCE_STRICT_CLIPPY || self.strict_clippy() {
-            clippy_cmd.args(["--profile", "test", "--", "-D", "warnings"]);
-        } else {
-            clippy_cmd.args(["--profile", "test"]);
-        }
+            // `--profile test` is required to also check code with `#[cfg(test)]`.
+            if FORCE_STRICT_CLIPPY || self.strict_clippy() {
+                clippy_cmd.args(["--profile", "test", "--", "-D", "warnings"]);
+            } else {
+                clippy_cmd.args(["--profile", "test"]);
+            }
 
-        let clippy_success = clippy_cmd.run("cargo clippy …")?;
-        let run_success = run_bin(bin_name, output, cmd_runner)?;
+            clippy_cmd.run("cargo clippy …")
+        })?;
+        if outcome.cancelled() {
+            return Ok(outcome);
+        }
+        outcome.run_stage(Stage::Run, &mut output, |output, _| {
+            run_bin(bin_name, output, cmd_runner, timeout)
+        })?;
 
-        Ok(clippy_success && run_success)
+        Ok(outcome)
     }
 
     /// Compile, check and run the exercise.
     /// The output is written to the `output` buffer after clearing it.
-    fn run_exercise(&self, output: Option<&mut Vec<u8>>, cmd_runner: &CmdRunner) -> Result<bool> {
+    fn run_exercise(
+        &self,
+        output: Option<&mut Vec<u8>>,
+        cmd_runner: &CmdRunner,
+    ) -> Result<RunOutcome> {
         self.run::<false>(self.name(), output, cmd_runner)
     }
 
     /// Compile, check and run the exercise's solution.
     /// The output is written to the `output` buffer after clearing it.
-    fn run_solution(&self, output: Option<&mut Vec<u8>>, cmd_runner: &CmdRunner) -> Result<bool> {
+    fn run_solution(
+        &self,
+        output: Option<&mut Vec<u8>>,
+        cmd_runner: &CmdRunner,
+    ) -> Result<RunOutcome> {
         let name = self.name();
         let mut bin_name = String::with_capacity(name.len() + 4);
         bin_name.push_str(name);
@@ -215,4 +449,8 @@ impl RunnableExercise for Exercise {
     fn test(&self) -> bool {
         self.test
     }
+
+    fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
 }
@@ -1,6 +1,6 @@
 use anyhow::{Context, Error, Result, bail};
 use serde::Deserialize;
-use std::{fs, io::ErrorKind};
+use std::{fs, io::ErrorKind, slice};
 
 use crate::{embedded::EMBEDDED_FILES, exercise::RunnableExercise};
 
@@ -17,16 +17,42 @@ pub struct ExerciseInfo {
     /// Deny all Clippy warnings.
     #[serde(default)]
     pub strict_clippy: bool,
+    /// Time limit in seconds for running the exercise's binary and tests.
+    /// Defaults to 10 seconds. `0` disables the limit.
+    pub timeout: Option<u64>,
     /// The exercise's hint to be shown to the user on request.
-    pub hint: &'static str,
+    pub hint: Hint,
     /// The exercise is already solved. Ignore it when checking that all exercises are unsolved.
     #[serde(default)]
     pub skip_check_unsolved: bool,
+    /// Names of exercises or directories whose exercises have to be done before this exercise.
+    #[serde(default)]
+    pub requires: Vec<&'static str>,
 }
 const fn default_true() -> bool {
     true
 }
 
+/// A single hint or a list of hint levels which are revealed one at a time.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Hint {
+    Single(&'static str),
+    Levels(Vec<&'static str>),
+}
+
+impl Hint {
+    /// The levels in the order of revealing them. Never empty.
+    pub fn levels(&self) -> &[&'static str] {
+        match self {
+            Self::Single(hint) => slice::from_ref(hint),
+            // Rejected by `dev check`.
+            Self::Levels(levels) if levels.is_empty() => &[""],
+            Self::Levels(levels) => levels,
+        }
+    }
+}
+
 impl ExerciseInfo {
     /// Path to the exercise file starting with the `exercises/` directory.
     pub fn path(&self) -> String {
@@ -69,6 +95,93 @@ impl RunnableExercise for ExerciseInfo {
     fn test(&self) -> bool {
         self.test
     }
+
+    fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+}
+
+/// A named subset of the exercises, e.g. for learners who already know the basics.
+#[derive(Deserialize)]
+pub struct TrackInfo {
+    pub name: &'static str,
+    /// Names of exercises or directories like in `requires`.
+    pub exercises: Vec<&'static str>,
+}
+
+/// The value of `--track` which selects all exercises.
+pub const ALL_TRACKS: &str = "all";
+
+// The indices of the exercise with the name `entry` or otherwise of all exercises in the
+// directory `entry`. Empty if neither exists.
+fn resolve_entry(exercises: &[impl RunnableExercise], entry: &str) -> Vec<usize> {
+    if let Some(ind) = exercises
+        .iter()
+        .position(|exercise| exercise.name() == entry)
+    {
+        return vec![ind];
+    }
+
+    exercises
+        .iter()
+        .enumerate()
+        .filter(|(_, exercise)| exercise.dir() == Some(entry))
+        .map(|(ind, _)| ind)
+        .collect()
+}
+
+/// Resolve the `requires` entries of all exercises to the indices of the required exercises.
+/// An entry is the name of an exercise or otherwise the name of a directory which requires all
+/// exercises in it (except the requiring exercise itself).
+pub fn prerequisites(exercise_infos: &[ExerciseInfo]) -> Result<Vec<Vec<usize>>> {
+    exercise_infos
+        .iter()
+        .enumerate()
+        .map(|(exercise_ind, exercise_info)| {
+            let mut prerequisites = Vec::new();
+
+            for &required in &exercise_info.requires {
+                let required_inds = resolve_entry(exercise_infos, required);
+                if required_inds.is_empty() {
+                    bail!(
+                        "The exercise `{}` requires `{required}` which is neither an exercise nor a directory in `info.toml`",
+                        exercise_info.name,
+                    );
+                }
+
+                prerequisites.extend(
+                    required_inds
+                        .into_iter()
+                        .filter(|&required_ind| required_ind != exercise_ind),
+                );
+            }
+
+            Ok(prerequisites)
+        })
+        .collect()
+}
+
+impl TrackInfo {
+    /// Whether each exercise is part of the track.
+    pub fn contained_exercises(&self, exercises: &[impl RunnableExercise]) -> Result<Vec<bool>> {
+        let mut contained = vec![false; exercises.len()];
+
+        for &entry in &self.exercises {
+            let inds = resolve_entry(exercises, entry);
+            if inds.is_empty() {
+                bail!(
+                    "The track `{}` contains `{entry}` which is neither an exercise nor a directory in `info.toml`",
+                    self.name,
+                );
+            }
+
+            for ind in inds {
+                contained[ind] = true;
+            }
+        }
+
+        Ok(contained)
+    }
 }
 
 /// The deserialized `info.toml` file.
@@ -82,6 +195,9 @@ pub struct InfoFile {
     pub final_message: Option<&'static str>,
     /// List of all exercises.
     pub exercises: Vec<ExerciseInfo>,
+    /// Tracks which can be selected with `--track`.
+    #[serde(default)]
+    pub tracks: Vec<TrackInfo>,
 }
 
 impl InfoFile {
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result, bail};
 use crossterm::{
     QueueableCommand,
-    style::{Attribute, Color, ResetColor, SetAttribute, SetForegroundColor},
+    style::{Attribute, ResetColor, SetAttribute},
 };
 use serde::Deserialize;
 use std::{
@@ -13,8 +13,12 @@ use std::{
 };
 
 use crate::{
-    cargo_toml::updated_cargo_toml, embedded::EMBEDDED_FILES, exercise::RunnableExercise,
-    info_file::InfoFile, term::press_enter_prompt,
+    cargo_toml::updated_cargo_toml,
+    embedded::EMBEDDED_FILES,
+    exercise::RunnableExercise,
+    info_file::InfoFile,
+    term::press_enter_prompt,
+    theme::{Style, set_style},
 };
 
 #[derive(Deserialize)]
@@ -192,7 +196,7 @@ pub fn init() -> Result<()> {
         }
     }
 
-    stdout.queue(SetForegroundColor(Color::Green))?;
+    set_style(&mut stdout, Style::Success)?;
     stdout.write_all("Initialization done ✓".as_bytes())?;
     stdout.queue(ResetColor)?;
     stdout.write_all(b"\n\n")?;
@@ -218,6 +222,10 @@ cargo.targetDir = true
 const GITIGNORE: &[u8] = b"Cargo.lock
 target/
 .vscode/
+.rustlings/
+.rustlings-state.txt.bak
+.rustlings-state.txt.tmp
+.rustlings-state.txt.lock
 ";
 
 pub const VS_CODE_EXTENSIONS_JSON: &[u8] = br#"{"recommendations":["rust-lang.rust-analyzer"]}"#;
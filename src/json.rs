@@ -0,0 +1,217 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::{self, Write};
+
+use crate::{
+    app_state::AppState,
+    diagnostics::Diagnostic,
+    exercise::{Exercise, StageOutcome},
+    state_file::ExerciseHistory,
+    term::strip_ansi,
+};
+
+/// Identifies an exercise in a JSON document.
+#[derive(Serialize)]
+pub struct ExerciseRef<'a> {
+    pub name: &'a str,
+    pub path: &'a str,
+}
+
+impl<'a> From<&'a Exercise> for ExerciseRef<'a> {
+    fn from(exercise: &'a Exercise) -> Self {
+        Self {
+            name: exercise.name,
+            path: exercise.path,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct StageDocument<'a> {
+    #[serde(flatten)]
+    pub outcome: &'a StageOutcome,
+    /// `None` if the stage's output was discarded because a later stage shows it again.
+    pub output: Option<String>,
+}
+
+impl<'a> StageDocument<'a> {
+    pub fn new(outcome: &'a StageOutcome, output: &[u8]) -> Self {
+        Self {
+            outcome,
+            output: outcome.output(output).map(strip_ansi),
+        }
+    }
+}
+
+/// Emitted by `rustlings run --format json`.
+#[derive(Serialize)]
+pub struct RunDocument<'a> {
+    pub exercise: ExerciseRef<'a>,
+    pub success: bool,
+    pub stages: Vec<StageDocument<'a>>,
+    /// Compiler diagnostics without duplicates.
+    pub diagnostics: &'a [Diagnostic],
+    /// The merged output of all commands without ANSI escape sequences.
+    pub output: String,
+    /// The hint levels revealed so far in the order of revealing them.
+    /// `rustlings hint` reveals the next one.
+    pub hints: &'a [&'static str],
+    /// `None` if all exercises are done.
+    pub next_pending: Option<ExerciseRef<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct ExerciseStatus<'a> {
+    #[serde(flatten)]
+    pub exercise: ExerciseRef<'a>,
+    pub done: bool,
+}
+
+/// Emitted by `rustlings check-all --format json`.
+#[derive(Serialize)]
+pub struct CheckAllDocument<'a> {
+    /// In the selected track.
+    pub exercises: Vec<ExerciseStatus<'a>>,
+    pub n_done: u32,
+    pub n_pending: u32,
+    /// `None` if all exercises are done.
+    pub next_pending: Option<ExerciseRef<'a>>,
+}
+
+impl<'a> CheckAllDocument<'a> {
+    pub fn new(app_state: &'a AppState, next_pending_exercise_ind: Option<usize>) -> Self {
+        let exercises = app_state.exercises();
+
+        Self {
+            exercises: exercises
+                .iter()
+                .filter(|exercise| exercise.in_track)
+                .map(|exercise| ExerciseStatus {
+                    exercise: ExerciseRef::from(exercise),
+                    done: exercise.done,
+                })
+                .collect(),
+            n_done: app_state.n_done(),
+            n_pending: app_state.n_pending(),
+            next_pending: next_pending_exercise_ind.map(|ind| ExerciseRef::from(&exercises[ind])),
+        }
+    }
+}
+
+/// Emitted by `rustlings hint --format json`.
+#[derive(Serialize)]
+pub struct HintDocument<'a> {
+    pub exercise: ExerciseRef<'a>,
+    /// The shown hint level starting at 1.
+    pub level: u32,
+    pub n_levels: u32,
+    pub hint: &'a str,
+}
+
+#[derive(Serialize)]
+pub struct TopicStats<'a> {
+    /// The exercises' directory. `None` for exercises directly in `exercises/`.
+    pub dir: Option<&'a str>,
+    pub n_done: u32,
+    pub n_exercises: u32,
+}
+
+#[derive(Serialize)]
+pub struct ExerciseStats<'a> {
+    #[serde(flatten)]
+    pub exercise: ExerciseRef<'a>,
+    pub done: bool,
+    #[serde(flatten)]
+    pub history: &'a ExerciseHistory,
+}
+
+/// Emitted by `rustlings stats --format json`.
+#[derive(Serialize)]
+pub struct StatsDocument<'a> {
+    /// The selected track. `None` if all exercises are selected.
+    pub track: Option<&'a str>,
+    /// In the selected track.
+    pub n_done: u32,
+    /// In the selected track.
+    pub n_exercises: u32,
+    /// In the order of the exercises of the selected track.
+    pub topics: Vec<TopicStats<'a>>,
+    /// The sum of the time from the first run until the first success of every done exercise
+    /// in the selected track. It includes breaks since it isn't the active time.
+    pub time_to_solve_secs: u64,
+    /// In the selected track.
+    pub n_runs: u32,
+    /// In the selected track.
+    pub n_hints_viewed: u32,
+    /// The number of exercises in the selected track whose hint was viewed at least once.
+    pub n_exercises_with_hints_viewed: u32,
+    /// In the selected track.
+    pub exercises: Vec<ExerciseStats<'a>>,
+}
+
+impl<'a> StatsDocument<'a> {
+    pub fn new(app_state: &'a AppState) -> Self {
+        let exercises = || {
+            app_state
+                .exercises()
+                .iter()
+                .filter(|exercise| exercise.in_track)
+        };
+
+        let mut topics = Vec::<TopicStats>::new();
+        for exercise in exercises() {
+            let topic = match topics.iter_mut().find(|topic| topic.dir == exercise.dir) {
+                Some(topic) => topic,
+                None => {
+                    topics.push(TopicStats {
+                        dir: exercise.dir,
+                        n_done: 0,
+                        n_exercises: 0,
+                    });
+                    topics.last_mut().unwrap()
+                }
+            };
+
+            topic.n_exercises += 1;
+            if exercise.done {
+                topic.n_done += 1;
+            }
+        }
+
+        let histories = || exercises().map(|exercise| &exercise.history);
+
+        Self {
+            track: app_state.track(),
+            n_done: app_state.n_done(),
+            n_exercises: app_state.n_exercises(),
+            topics,
+            time_to_solve_secs: histories()
+                .filter_map(|history| {
+                    Some(history.finished_at?.saturating_sub(history.started_at?))
+                })
+                .sum(),
+            n_runs: histories().map(|history| history.runs).sum(),
+            n_hints_viewed: histories().map(|history| history.hints_viewed).sum(),
+            n_exercises_with_hints_viewed: histories()
+                .filter(|history| history.hints_viewed > 0)
+                .count() as u32,
+            exercises: exercises()
+                .map(|exercise| ExerciseStats {
+                    exercise: ExerciseRef::from(exercise),
+                    done: exercise.done,
+                    history: &exercise.history,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Write a JSON document followed by a newline to stdout.
+pub fn print(document: &impl Serialize) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    serde_json::ser::to_writer_pretty(&mut stdout, document)
+        .context("Failed to write the JSON output")?;
+    stdout.write_all(b"\n")?;
+
+    Ok(())
+}
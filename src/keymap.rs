@@ -0,0 +1,438 @@
+use anyhow::{Result, bail};
+use crossterm::event::KeyCode;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// One key or a list of keys bound to an action in the config file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> &[String] {
+        match self {
+            Self::Single(key) => std::slice::from_ref(key),
+            Self::Multiple(keys) => keys,
+        }
+    }
+}
+
+/// The `[keymap]` table of the config file. Maps action names to keys per mode.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeymapConfig {
+    pub watch: HashMap<String, KeyList>,
+    pub list: HashMap<String, KeyList>,
+}
+
+fn parse_key(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+
+    let code = match key.to_ascii_lowercase().as_str() {
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+/// The label of a key shown in the prompts and the help.
+pub fn key_label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => String::from("space"),
+        KeyCode::Char(c) => String::from(c),
+        KeyCode::Enter => String::from("↩"),
+        KeyCode::Up => String::from("↑"),
+        KeyCode::Down => String::from("↓"),
+        code => code.to_string().to_ascii_lowercase(),
+    }
+}
+
+pub trait Action: Copy + Eq + 'static {
+    /// All actions in the order shown in the help.
+    const ALL: &'static [Self];
+    /// The name of the action in the config file.
+    fn name(self) -> &'static str;
+    /// The description shown in the help.
+    fn description(self) -> &'static str;
+    fn default_keys(self) -> &'static [KeyCode];
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WatchAction {
+    Next,
+    Run,
+    Skip,
+    Hint,
+    Diff,
+    Notes,
+    List,
+    CheckAll,
+    Reset,
+    Help,
+    Quit,
+}
+
+impl Action for WatchAction {
+    const ALL: &'static [Self] = &[
+        Self::Next,
+        Self::Run,
+        Self::Skip,
+        Self::Hint,
+        Self::Diff,
+        Self::Notes,
+        Self::List,
+        Self::CheckAll,
+        Self::Reset,
+        Self::Help,
+        Self::Quit,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Next => "next",
+            Self::Run => "run",
+            Self::Skip => "skip",
+            Self::Hint => "hint",
+            Self::Diff => "diff",
+            Self::Notes => "notes",
+            Self::List => "list",
+            Self::CheckAll => "check-all",
+            Self::Reset => "reset",
+            Self::Help => "help",
+            Self::Quit => "quit",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Next => "Move on to the next exercise after the current one is done",
+            Self::Run => "Run the current exercise (only with `--manual-run`)",
+            Self::Skip => "Skip the current exercise and revisit it at the end",
+            Self::Hint => "Show the hint or its next level",
+            Self::Diff => "Show or hide the diff between the done exercise and its solution",
+            Self::Notes => "Open your notes on the current exercise",
+            Self::List => "Open the list of all exercises",
+            Self::CheckAll => "Check all exercises",
+            Self::Reset => "Reset the current exercise",
+            Self::Help => "Show or hide this help",
+            Self::Quit => "Quit Rustlings (or cancel checking exercises)",
+        }
+    }
+
+    fn default_keys(self) -> &'static [KeyCode] {
+        match self {
+            Self::Next => &[KeyCode::Char('n')],
+            Self::Run => &[KeyCode::Char('r')],
+            Self::Skip => &[KeyCode::Char('s')],
+            Self::Hint => &[KeyCode::Char('h')],
+            Self::Diff => &[KeyCode::Char('d')],
+            Self::Notes => &[KeyCode::Char('o')],
+            Self::List => &[KeyCode::Char('l')],
+            Self::CheckAll => &[KeyCode::Char('c')],
+            Self::Reset => &[KeyCode::Char('x')],
+            Self::Help => &[KeyCode::Char('?')],
+            Self::Quit => &[KeyCode::Char('q')],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ListAction {
+    Down,
+    Up,
+    First,
+    Last,
+    Continue,
+    Reset,
+    Mark,
+    Search,
+    FilterDone,
+    FilterPending,
+    FilterSkipped,
+    Help,
+    Quit,
+}
+
+impl Action for ListAction {
+    const ALL: &'static [Self] = &[
+        Self::Down,
+        Self::Up,
+        Self::First,
+        Self::Last,
+        Self::Continue,
+        Self::Reset,
+        Self::Mark,
+        Self::Search,
+        Self::FilterDone,
+        Self::FilterPending,
+        Self::FilterSkipped,
+        Self::Help,
+        Self::Quit,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Down => "down",
+            Self::Up => "up",
+            Self::First => "first",
+            Self::Last => "last",
+            Self::Continue => "continue",
+            Self::Reset => "reset",
+            Self::Mark => "mark",
+            Self::Search => "search",
+            Self::FilterDone => "filter-done",
+            Self::FilterPending => "filter-pending",
+            Self::FilterSkipped => "filter-skipped",
+            Self::Help => "help",
+            Self::Quit => "quit",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Down => "Select the next exercise",
+            Self::Up => "Select the previous exercise",
+            Self::First => "Select the first exercise",
+            Self::Last => "Select the last exercise",
+            Self::Continue => "Continue at the selected exercise",
+            Self::Reset => "Reset the marked exercises or the selected one",
+            Self::Mark => "Mark or unmark the selected exercise",
+            Self::Search => "Search for an exercise by its name",
+            Self::FilterDone => "Only show done exercises",
+            Self::FilterPending => "Only show pending exercises",
+            Self::FilterSkipped => "Only show skipped exercises",
+            Self::Help => "Show this help",
+            Self::Quit => "Quit the list",
+        }
+    }
+
+    fn default_keys(self) -> &'static [KeyCode] {
+        match self {
+            Self::Down => &[KeyCode::Down, KeyCode::Char('j')],
+            Self::Up => &[KeyCode::Up, KeyCode::Char('k')],
+            Self::First => &[KeyCode::Home, KeyCode::Char('g')],
+            Self::Last => &[KeyCode::End, KeyCode::Char('G')],
+            Self::Continue => &[KeyCode::Char('c'), KeyCode::Enter],
+            Self::Reset => &[KeyCode::Char('r')],
+            Self::Mark => &[KeyCode::Char('m')],
+            Self::Search => &[KeyCode::Char('s'), KeyCode::Char('/')],
+            Self::FilterDone => &[KeyCode::Char('d')],
+            Self::FilterPending => &[KeyCode::Char('p')],
+            Self::FilterSkipped => &[KeyCode::Char('S')],
+            Self::Help => &[KeyCode::Char('?')],
+            Self::Quit => &[KeyCode::Char('q')],
+        }
+    }
+}
+
+/// The keys bound to the actions of one mode.
+pub struct Bindings<A> {
+    // In the order of `Action::ALL`. Every action has at least one key.
+    bindings: Vec<(A, Vec<KeyCode>)>,
+}
+
+impl<A: Action> Bindings<A> {
+    // `mode` is the name of the table in the config file.
+    fn new(overrides: &HashMap<String, KeyList>, mode: &str) -> Result<Self> {
+        for name in overrides.keys() {
+            if !A::ALL.iter().any(|action| action.name() == name) {
+                let available = A::ALL
+                    .iter()
+                    .map(|action| action.name())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                bail!(
+                    "Unknown action `{name}` in `[keymap.{mode}]`. The available actions: {available}"
+                );
+            }
+        }
+
+        let mut bindings = Vec::with_capacity(A::ALL.len());
+        for &action in A::ALL {
+            let keys = match overrides.get(action.name()) {
+                Some(key_list) => {
+                    let mut keys = Vec::with_capacity(key_list.keys().len());
+                    for key in key_list.keys() {
+                        let Some(code) = parse_key(key) else {
+                            bail!(
+                                "Invalid key `{key}` for `{}` in `[keymap.{mode}]`. \
+                                 Use a single character or one of enter, esc, tab, space, up, down, left, right, home, end, pageup, pagedown",
+                                action.name(),
+                            );
+                        };
+                        keys.push(code);
+                    }
+                    keys
+                }
+                None => action.default_keys().to_vec(),
+            };
+
+            if keys.is_empty() {
+                bail!(
+                    "No key is bound to `{}` in `[keymap.{mode}]`",
+                    action.name()
+                );
+            }
+
+            bindings.push((action, keys));
+        }
+
+        for (ind, (action, keys)) in bindings.iter().enumerate() {
+            for (other_action, other_keys) in &bindings[ind + 1..] {
+                if let Some(&code) = keys.iter().find(|code| other_keys.contains(code)) {
+                    bail!(
+                        "The key `{}` is bound to both `{}` and `{}` in `[keymap.{mode}]`",
+                        key_label(code),
+                        action.name(),
+                        other_action.name(),
+                    );
+                }
+            }
+        }
+
+        Ok(Self { bindings })
+    }
+
+    /// The action bound to a key.
+    pub fn action(&self, code: KeyCode) -> Option<A> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&code))
+            .map(|(action, _)| *action)
+    }
+
+    /// The label of the first key bound to an action.
+    pub fn primary_label(&self, action: A) -> String {
+        key_label(self.keys(action)[0])
+    }
+
+    /// The labels of all keys bound to an action separated by `separator`.
+    pub fn label(&self, action: A, separator: &str) -> String {
+        self.keys(action)
+            .iter()
+            .map(|&code| key_label(code))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    fn keys(&self, action: A) -> &[KeyCode] {
+        self.bindings
+            .iter()
+            .find(|(other, _)| *other == action)
+            .map_or(&[], |(_, keys)| keys)
+    }
+
+    /// The lines of the help: The keys of each action followed by its description.
+    pub fn help_lines(&self) -> Vec<String> {
+        let labels = A::ALL
+            .iter()
+            .map(|&action| self.label(action, ", "))
+            .collect::<Vec<_>>();
+        let width = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        A::ALL
+            .iter()
+            .zip(labels)
+            .map(|(action, label)| {
+                let padding = width - label.chars().count();
+                format!("  {label}{:padding$}  {}", "", action.description())
+            })
+            .collect()
+    }
+}
+
+/// The key bindings of the watch mode and the list.
+pub struct Keymap {
+    pub watch: Bindings<WatchAction>,
+    pub list: Bindings<ListAction>,
+}
+
+impl Keymap {
+    /// The default bindings with the ones from the config file replacing them per action.
+    pub fn new(config: &KeymapConfig) -> Result<Self> {
+        Ok(Self {
+            watch: Bindings::new(&config.watch, "watch")?,
+            list: Bindings::new(&config.list, "list")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(watch: Vec<(&str, Vec<&str>)>) -> Result<Keymap> {
+        Keymap::new(&KeymapConfig {
+            watch: watch
+                .into_iter()
+                .map(|(name, keys)| {
+                    let keys = keys.into_iter().map(String::from).collect();
+                    (String::from(name), KeyList::Multiple(keys))
+                })
+                .collect(),
+            list: HashMap::new(),
+        })
+    }
+
+    #[test]
+    fn defaults() {
+        let keymap = Keymap::new(&KeymapConfig::default()).unwrap();
+        assert_eq!(
+            keymap.watch.action(KeyCode::Char('n')),
+            Some(WatchAction::Next),
+        );
+        assert_eq!(
+            keymap.list.action(KeyCode::Enter),
+            Some(ListAction::Continue)
+        );
+        assert_eq!(keymap.list.label(ListAction::Down, "/"), "↓/j");
+        assert_eq!(keymap.watch.action(KeyCode::Char('z')), None);
+    }
+
+    #[test]
+    fn overrides() {
+        let keymap = keymap(vec![("next", vec!["N", "enter"]), ("quit", vec!["Q"])]).unwrap();
+        assert_eq!(keymap.watch.action(KeyCode::Char('n')), None);
+        assert_eq!(keymap.watch.action(KeyCode::Enter), Some(WatchAction::Next));
+        assert_eq!(keymap.watch.primary_label(WatchAction::Next), "N");
+        assert_eq!(keymap.watch.primary_label(WatchAction::Quit), "Q");
+        assert_eq!(keymap.watch.primary_label(WatchAction::Hint), "h");
+    }
+
+    #[test]
+    fn invalid() {
+        let err = |watch| keymap(watch).err().unwrap().to_string();
+        assert_eq!(
+            err(vec![("next", vec!["h"])]),
+            "The key `h` is bound to both `next` and `hint` in `[keymap.watch]`",
+        );
+        assert!(err(vec![("jump", vec!["j"])]).starts_with("Unknown action `jump`"));
+        assert!(err(vec![("next", vec!["ctrl-n"])]).starts_with("Invalid key `ctrl-n`"));
+        assert_eq!(
+            err(vec![("quit", vec![])]),
+            "No key is bound to `quit` in `[keymap.watch]`",
+        );
+    }
+}
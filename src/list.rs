@@ -13,14 +13,19 @@ use std::io::{self, StdoutLock, Write};
 
 use crate::{
     app_state::AppState,
+    keymap::{Bindings, ListAction},
     list::state::{Filter, ListState},
 };
 
 mod scroll_state;
 mod state;
 
-fn handle_list(app_state: &mut AppState, stdout: &mut StdoutLock) -> Result<()> {
-    let mut list_state = ListState::build(app_state, stdout)?;
+fn handle_list(
+    app_state: &mut AppState,
+    bindings: &Bindings<ListAction>,
+    stdout: &mut StdoutLock,
+) -> Result<()> {
+    let mut list_state = ListState::build(app_state, bindings, stdout)?;
     let mut is_searching = false;
 
     loop {
@@ -54,47 +59,36 @@ fn handle_list(app_state: &mut AppState, stdout: &mut StdoutLock) -> Result<()>
                     continue;
                 }
 
-                match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Down | KeyCode::Char('j') => list_state.select_next(),
-                    KeyCode::Up | KeyCode::Char('k') => list_state.select_previous(),
-                    KeyCode::Home | KeyCode::Char('g') => list_state.select_first(),
-                    KeyCode::End | KeyCode::Char('G') => list_state.select_last(),
-                    KeyCode::Char('d') => {
-                        if list_state.filter() == Filter::Done {
-                            list_state.set_filter(Filter::None);
-                            list_state.message.push_str("Disabled filter DONE");
-                        } else {
-                            list_state.set_filter(Filter::Done);
-                            list_state.message.push_str(
-                                "Enabled filter DONE │ Press d again to disable the filter",
-                            );
-                        }
-                    }
-                    KeyCode::Char('p') => {
-                        if list_state.filter() == Filter::Pending {
-                            list_state.set_filter(Filter::None);
-                            list_state.message.push_str("Disabled filter PENDING");
-                        } else {
-                            list_state.set_filter(Filter::Pending);
-                            list_state.message.push_str(
-                                "Enabled filter PENDING │ Press p again to disable the filter",
-                            );
-                        }
-                    }
-                    KeyCode::Char('r') => list_state.reset_selected()?,
-                    KeyCode::Char('c') | KeyCode::Enter => {
+                if list_state.show_help {
+                    list_state.show_help = false;
+                    list_state.draw(stdout)?;
+                    continue;
+                }
+
+                match bindings.action(key.code) {
+                    Some(ListAction::Quit) => return Ok(()),
+                    Some(ListAction::Down) => list_state.select_next(),
+                    Some(ListAction::Up) => list_state.select_previous(),
+                    Some(ListAction::First) => list_state.select_first(),
+                    Some(ListAction::Last) => list_state.select_last(),
+                    Some(ListAction::FilterDone) => list_state.toggle_filter(Filter::Done)?,
+                    Some(ListAction::FilterPending) => list_state.toggle_filter(Filter::Pending)?,
+                    Some(ListAction::FilterSkipped) => list_state.toggle_filter(Filter::Skipped)?,
+                    Some(ListAction::Reset) => list_state.reset_selected(stdout)?,
+                    Some(ListAction::Mark) => list_state.toggle_mark_selected()?,
+                    Some(ListAction::Continue) => {
                         if list_state.selected_to_current_exercise()? {
                             return Ok(());
                         }
                     }
-                    KeyCode::Char('s' | '/') => {
+                    Some(ListAction::Search) => {
                         is_searching = true;
                         list_state.apply_search_query();
                     }
+                    Some(ListAction::Help) => list_state.show_help = true,
                     // Redraw to remove the message.
-                    KeyCode::Esc => (),
-                    _ => continue,
+                    None if key.code == KeyCode::Esc => (),
+                    None => continue,
                 }
             }
             Event::Mouse(event) => match event.kind {
@@ -111,7 +105,7 @@ fn handle_list(app_state: &mut AppState, stdout: &mut StdoutLock) -> Result<()>
     }
 }
 
-pub fn list(app_state: &mut AppState) -> Result<()> {
+pub fn list(app_state: &mut AppState, bindings: &Bindings<ListAction>) -> Result<()> {
     let mut stdout = io::stdout().lock();
     stdout
         .queue(EnterAlternateScreen)?
@@ -120,7 +114,7 @@ pub fn list(app_state: &mut AppState) -> Result<()> {
         .queue(EnableMouseCapture)?;
     enable_raw_mode()?;
 
-    let res = handle_list(app_state, &mut stdout);
+    let res = handle_list(app_state, bindings, &mut stdout);
 
     // Restore the terminal even if we got an error.
     stdout
@@ -2,9 +2,8 @@ use anyhow::{Context, Result};
 use crossterm::{
     QueueableCommand,
     cursor::{MoveTo, MoveToNextLine},
-    style::{
-        Attribute, Attributes, Color, ResetColor, SetAttribute, SetAttributes, SetForegroundColor,
-    },
+    event::{self, Event, KeyCode, KeyEventKind},
+    style::{Attribute, Attributes, ResetColor, SetAttribute, SetAttributes},
     terminal::{self, BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate},
 };
 use std::{
@@ -15,8 +14,11 @@ use std::{
 use crate::{
     app_state::AppState,
     exercise::Exercise,
+    keymap::{Bindings, ListAction},
     list::scroll_state::ScrollState,
+    notes,
     term::{CountedWrite, MaxLenWriter, progress_bar},
+    theme::{Style, set_style, unset_style},
 };
 
 const COL_SPACING: usize = 2;
@@ -24,6 +26,16 @@ const SELECTED_ROW_ATTRIBUTES: Attributes = Attributes::none()
     .with(Attribute::Reverse)
     .with(Attribute::Bold);
 
+// Unsetting a style can also remove the attributes of the selected row.
+fn unset_style_in_row(stdout: &mut StdoutLock, style: Style, selected: bool) -> io::Result<()> {
+    unset_style(stdout, style)?;
+    if selected {
+        stdout.queue(SetAttributes(SELECTED_ROW_ATTRIBUTES))?;
+    }
+
+    Ok(())
+}
+
 fn next_ln(stdout: &mut StdoutLock) -> io::Result<()> {
     stdout
         .queue(Clear(ClearType::UntilNewLine))?
@@ -35,15 +47,34 @@ fn next_ln(stdout: &mut StdoutLock) -> io::Result<()> {
 pub enum Filter {
     Done,
     Pending,
+    Skipped,
     None,
 }
 
+impl Filter {
+    fn matches(self, exercise: &Exercise) -> bool {
+        match self {
+            Self::Done => exercise.done,
+            Self::Pending => !exercise.done,
+            Self::Skipped => !exercise.done && exercise.skipped,
+            Self::None => true,
+        }
+    }
+}
+
 pub struct ListState<'a> {
     /// Footer message to be displayed if not empty.
     pub message: String,
     pub search_query: String,
+    /// Whether the help with the key bindings is shown instead of the exercises.
+    pub show_help: bool,
     app_state: &'a mut AppState,
+    bindings: &'a Bindings<ListAction>,
     scroll_state: ScrollState,
+    // Indexed by the exercise index.
+    marked: Vec<bool>,
+    // Indexed by the exercise index.
+    has_notes: Vec<bool>,
     name_col_padding: Vec<u8>,
     path_col_padding: Vec<u8>,
     filter: Filter,
@@ -53,7 +84,11 @@ pub struct ListState<'a> {
 }
 
 impl<'a> ListState<'a> {
-    pub fn build(app_state: &'a mut AppState, stdout: &mut StdoutLock) -> Result<Self> {
+    pub fn build(
+        app_state: &'a mut AppState,
+        bindings: &'a Bindings<ListAction>,
+        stdout: &mut StdoutLock,
+    ) -> Result<Self> {
         stdout.queue(Clear(ClearType::All))?;
 
         let name_col_title_len = 4;
@@ -80,7 +115,15 @@ impl<'a> ListState<'a> {
         let mut slf = Self {
             message: String::with_capacity(128),
             search_query: String::new(),
+            show_help: false,
+            marked: vec![false; app_state.exercises().len()],
+            has_notes: app_state
+                .exercises()
+                .iter()
+                .map(|exercise| notes::has_notes(exercise.name))
+                .collect(),
             app_state,
+            bindings,
             scroll_state,
             name_col_padding,
             path_col_padding,
@@ -128,9 +171,9 @@ impl<'a> ListState<'a> {
                 })
         {
             writer.write_str(pre_highlight)?;
-            writer.stdout.queue(SetForegroundColor(Color::Magenta))?;
+            set_style(writer.stdout, Style::Highlight)?;
             writer.write_str(highlight)?;
-            writer.stdout.queue(SetForegroundColor(Color::Reset))?;
+            unset_style(writer.stdout, Style::Highlight)?;
             return writer.write_str(post_highlight);
         }
 
@@ -152,7 +195,8 @@ impl<'a> ListState<'a> {
         {
             let mut writer = MaxLenWriter::new(stdout, self.term_width as usize);
 
-            if self.scroll_state.selected() == Some(row_offset + n_displayed_rows) {
+            let selected = self.scroll_state.selected() == Some(row_offset + n_displayed_rows);
+            if selected {
                 // The crab emoji has the width of two ascii chars.
                 writer.add_to_len(2);
                 writer.stdout.write_all("🦀".as_bytes())?;
@@ -163,23 +207,41 @@ impl<'a> ListState<'a> {
                 writer.write_ascii(b"  ")?;
             }
 
+            if self.marked[exercise_ind] {
+                writer.write_ascii(b"* ")?;
+            } else {
+                writer.write_ascii(b"  ")?;
+            }
+
             if exercise_ind == current_exercise_ind {
-                writer.stdout.queue(SetForegroundColor(Color::Red))?;
-                writer.write_ascii(b">>>>>>>  ")?;
+                set_style(writer.stdout, Style::Current)?;
+                writer.write_ascii(b">>>>>>>")?;
+                unset_style_in_row(writer.stdout, Style::Current, selected)?;
+                writer.write_ascii(b"  ")?;
             } else {
                 writer.write_ascii(b"         ")?;
             }
 
-            if exercise.done {
-                writer.stdout.queue(SetForegroundColor(Color::Green))?;
-                writer.write_ascii(b"DONE   ")?;
+            let (state_style, state) = if exercise.done {
+                (Style::Success, b"DONE   ")
+            } else if exercise.skipped {
+                (Style::Skipped, b"SKIPPED")
+            } else if self.app_state.is_locked(exercise_ind) {
+                (Style::Muted, b"LOCKED ")
             } else {
-                writer.stdout.queue(SetForegroundColor(Color::Yellow))?;
-                writer.write_ascii(b"PENDING")?;
-            }
-            writer.stdout.queue(SetForegroundColor(Color::Reset))?;
+                (Style::Pending, b"PENDING")
+            };
+            set_style(writer.stdout, state_style)?;
+            writer.write_ascii(state)?;
+            unset_style_in_row(writer.stdout, state_style, selected)?;
             writer.write_ascii(b"  ")?;
 
+            if self.has_notes[exercise_ind] {
+                writer.write_str("  ✎    ")?;
+            } else {
+                writer.write_ascii(b"       ")?;
+            }
+
             self.draw_exercise_name(&mut writer, exercise)?;
 
             writer.write_ascii(&self.name_col_padding[exercise.name.len()..])?;
@@ -203,21 +265,25 @@ impl<'a> ListState<'a> {
 
         stdout.queue(BeginSynchronizedUpdate)?.queue(MoveTo(0, 0))?;
 
-        // Header
-        let mut writer = MaxLenWriter::new(stdout, self.term_width as usize);
-        writer.write_ascii(b"  Current  State    Name")?;
-        writer.write_ascii(&self.name_col_padding[4..])?;
-        writer.write_ascii(b"Path")?;
-        next_ln(stdout)?;
+        let n_displayed_rows = if self.show_help {
+            self.draw_help(stdout)?
+        } else {
+            // Header
+            let mut writer = MaxLenWriter::new(stdout, self.term_width as usize);
+            writer.write_ascii(b"    Current  State    Notes  Name")?;
+            writer.write_ascii(&self.name_col_padding[4..])?;
+            writer.write_ascii(b"Path")?;
+            next_ln(stdout)?;
 
-        // Rows
-        let iter = self.app_state.exercises().iter().enumerate();
-        let n_displayed_rows = match self.filter {
-            Filter::Done => self.draw_rows(stdout, iter.filter(|(_, exercise)| exercise.done))?,
-            Filter::Pending => {
-                self.draw_rows(stdout, iter.filter(|(_, exercise)| !exercise.done))?
-            }
-            Filter::None => self.draw_rows(stdout, iter)?,
+            // Rows
+            self.draw_rows(
+                stdout,
+                self.app_state
+                    .exercises()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, exercise)| self.filter.matches(exercise)),
+            )?
         };
 
         for _ in 0..self.scroll_state.max_n_rows_to_display() - n_displayed_rows {
@@ -228,7 +294,7 @@ impl<'a> ListState<'a> {
             progress_bar(
                 &mut MaxLenWriter::new(stdout, self.term_width as usize),
                 self.app_state.n_done(),
-                self.app_state.exercises().len() as u32,
+                self.app_state.n_exercises(),
                 self.term_width,
             )?;
             next_ln(stdout)?;
@@ -236,69 +302,67 @@ impl<'a> ListState<'a> {
             let mut writer = MaxLenWriter::new(stdout, self.term_width as usize);
             if self.message.is_empty() {
                 // Help footer message
-                if self.scroll_state.selected().is_some() {
-                    writer.write_str("↓/")?;
-                    hotkey(&mut writer, b"j")?;
-                    writer.write_str(" ↑/")?;
-                    hotkey(&mut writer, b"k")?;
-                    writer.write_ascii(b" home/")?;
-                    hotkey(&mut writer, b"g")?;
-                    writer.write_ascii(b" end/")?;
-                    hotkey(&mut writer, b"G")?;
-                    writer.write_str(" | ↩️/")?;
-                    hotkey(&mut writer, b"c")?;
-                    writer.write_ascii(b"ontinue at | ")?;
-                    hotkey(&mut writer, b"r")?;
-                    writer.write_ascii(b"eset exercise")?;
-                    next_ln(stdout)?;
-                    writer = MaxLenWriter::new(stdout, self.term_width as usize);
-
-                    hotkey(&mut writer, b"s")?;
-                    writer.write_ascii(b"earch | filter ")?;
+                let bindings = self.bindings;
+                if self.show_help {
+                    writer.write_ascii(b"Press any key to close the help")?;
                 } else {
-                    // Nothing selected (and nothing shown), so only display filter and quit.
-                    writer.write_ascii(b"filter ")?;
-                }
-
-                match self.filter {
-                    Filter::Done => {
-                        writer.stdout.queue(SetAttribute(Attribute::Underlined))?;
-                        hotkey(&mut writer, b"d")?;
-                        writer
-                            .stdout
-                            .queue(SetForegroundColor(Color::Magenta))?
-                            .queue(SetAttribute(Attribute::Underlined))?;
-                        writer.write_str("one")?;
-                        writer.stdout.queue(ResetColor)?;
-                        writer.write_ascii(b"/")?;
-                        hotkey(&mut writer, b"p")?;
-                        writer.write_ascii(b"ending")?;
+                    if self.scroll_state.selected().is_some() {
+                        writer.write_str(&bindings.label(ListAction::Down, "/"))?;
+                        writer.write_ascii(b" ")?;
+                        writer.write_str(&bindings.label(ListAction::Up, "/"))?;
+                        writer.write_ascii(b" ")?;
+                        writer.write_str(&bindings.label(ListAction::First, "/"))?;
+                        writer.write_ascii(b" ")?;
+                        writer.write_str(&bindings.label(ListAction::Last, "/"))?;
+                        writer.write_ascii(b" | ")?;
+                        hotkey(&mut writer, bindings, ListAction::Continue)?;
+                        writer.write_ascii(b":continue at | ")?;
+                        hotkey(&mut writer, bindings, ListAction::Reset)?;
+                        writer.write_ascii(b":reset exercise")?;
+                        next_ln(stdout)?;
+                        writer = MaxLenWriter::new(stdout, self.term_width as usize);
+
+                        hotkey(&mut writer, bindings, ListAction::Search)?;
+                        writer.write_ascii(b":search | ")?;
+                        hotkey(&mut writer, bindings, ListAction::Mark)?;
+                        writer.write_ascii(b":mark | filter ")?;
+                    } else {
+                        // Nothing selected (and nothing shown), so only display filter and quit.
+                        writer.write_ascii(b"filter ")?;
                     }
-                    Filter::Pending => {
-                        hotkey(&mut writer, b"d")?;
-                        writer.write_ascii(b"one/")?;
-                        writer.stdout.queue(SetAttribute(Attribute::Underlined))?;
-                        hotkey(&mut writer, b"p")?;
-                        writer
-                            .stdout
-                            .queue(SetForegroundColor(Color::Magenta))?
-                            .queue(SetAttribute(Attribute::Underlined))?;
-                        writer.write_ascii(b"ending")?;
-                        writer.stdout.queue(ResetColor)?;
-                    }
-                    Filter::None => {
-                        hotkey(&mut writer, b"d")?;
-                        writer.write_ascii(b"one/")?;
-                        hotkey(&mut writer, b"p")?;
-                        writer.write_ascii(b"ending")?;
-                    }
-                }
 
-                writer.write_ascii(b" | ")?;
-                hotkey(&mut writer, b"q")?;
-                writer.write_ascii(b"uit list")?;
+                    filter_hotkey(
+                        &mut writer,
+                        bindings,
+                        ListAction::FilterDone,
+                        b"done",
+                        self.filter == Filter::Done,
+                    )?;
+                    writer.write_ascii(b"/")?;
+                    filter_hotkey(
+                        &mut writer,
+                        bindings,
+                        ListAction::FilterPending,
+                        b"pending",
+                        self.filter == Filter::Pending,
+                    )?;
+                    writer.write_ascii(b"/")?;
+                    filter_hotkey(
+                        &mut writer,
+                        bindings,
+                        ListAction::FilterSkipped,
+                        b"skipped",
+                        self.filter == Filter::Skipped,
+                    )?;
+
+                    writer.write_ascii(b" | ")?;
+                    hotkey(&mut writer, bindings, ListAction::Help)?;
+                    writer.write_ascii(b":help | ")?;
+                    hotkey(&mut writer, bindings, ListAction::Quit)?;
+                    writer.write_ascii(b":quit list")?;
+                }
             } else {
-                writer.stdout.queue(SetForegroundColor(Color::Magenta))?;
+                set_style(writer.stdout, Style::Highlight)?;
                 writer.write_str(&self.message)?;
                 stdout.queue(ResetColor)?;
                 next_ln(stdout)?;
@@ -311,34 +375,66 @@ impl<'a> ListState<'a> {
     }
 
     fn update_rows(&mut self) {
-        let n_rows = match self.filter {
-            Filter::Done => self
-                .app_state
-                .exercises()
-                .iter()
-                .filter(|exercise| exercise.done)
-                .count(),
-            Filter::Pending => self
-                .app_state
-                .exercises()
-                .iter()
-                .filter(|exercise| !exercise.done)
-                .count(),
-            Filter::None => self.app_state.exercises().len(),
-        };
+        let n_rows = self
+            .app_state
+            .exercises()
+            .iter()
+            .filter(|exercise| self.filter.matches(exercise))
+            .count();
 
         self.scroll_state.set_n_rows(n_rows);
     }
 
-    pub fn filter(&self) -> Filter {
-        self.filter
-    }
-
-    pub fn set_filter(&mut self, filter: Filter) {
+    fn set_filter(&mut self, filter: Filter) {
         self.filter = filter;
         self.update_rows();
     }
 
+    /// Enable the filter or disable it if it is already enabled.
+    pub fn toggle_filter(&mut self, filter: Filter) -> Result<()> {
+        let (name, action) = match filter {
+            Filter::Done => ("DONE", ListAction::FilterDone),
+            Filter::Pending => ("PENDING", ListAction::FilterPending),
+            Filter::Skipped => ("SKIPPED", ListAction::FilterSkipped),
+            Filter::None => return Ok(()),
+        };
+
+        if self.filter == filter {
+            self.set_filter(Filter::None);
+            write!(self.message, "Disabled filter {name}")?;
+        } else {
+            self.set_filter(filter);
+            write!(
+                self.message,
+                "Enabled filter {name} │ Press {} again to disable the filter",
+                self.bindings.primary_label(action),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Draw the key bindings instead of the exercises. Returns the number of drawn lines.
+    fn draw_help(&self, stdout: &mut StdoutLock) -> io::Result<usize> {
+        let mut writer = MaxLenWriter::new(stdout, self.term_width as usize);
+        writer.write_ascii(b"Key bindings")?;
+        next_ln(stdout)?;
+
+        let mut n_lines = 0;
+        for line in self
+            .bindings
+            .help_lines()
+            .iter()
+            .take(self.scroll_state.max_n_rows_to_display())
+        {
+            MaxLenWriter::new(stdout, self.term_width as usize).write_str(line)?;
+            next_ln(stdout)?;
+            n_lines += 1;
+        }
+
+        Ok(n_lines)
+    }
+
     pub fn select_next(&mut self) {
         self.scroll_state.select_next();
     }
@@ -356,46 +452,185 @@ impl<'a> ListState<'a> {
     }
 
     fn selected_to_exercise_ind(&self, selected: usize) -> Result<usize> {
-        match self.filter {
-            Filter::Done => self
-                .app_state
-                .exercises()
-                .iter()
-                .enumerate()
-                .filter(|(_, exercise)| exercise.done)
-                .nth(selected)
-                .context("Invalid selection index")
-                .map(|(ind, _)| ind),
-            Filter::Pending => self
-                .app_state
-                .exercises()
-                .iter()
-                .enumerate()
-                .filter(|(_, exercise)| !exercise.done)
-                .nth(selected)
-                .context("Invalid selection index")
-                .map(|(ind, _)| ind),
-            Filter::None => Ok(selected),
+        self.app_state
+            .exercises()
+            .iter()
+            .enumerate()
+            .filter(|(_, exercise)| self.filter.matches(exercise))
+            .nth(selected)
+            .context("Invalid selection index")
+            .map(|(ind, _)| ind)
+    }
+
+    // Show the content above the prompt on a cleared screen and wait for `y` or `n`.
+    fn confirm(&self, stdout: &mut StdoutLock, content: &[u8], prompt: &str) -> Result<bool> {
+        stdout
+            .queue(BeginSynchronizedUpdate)?
+            .queue(Clear(ClearType::All))?
+            .queue(MoveTo(0, 0))?;
+
+        // Keep 2 lines for the prompt.
+        let max_n_lines = (self.term_height as usize).saturating_sub(2);
+        let content = content.strip_suffix(b"\n").unwrap_or(content);
+        for (ind, line) in content.split(|c| *c == b'\n').enumerate() {
+            if ind + 1 == max_n_lines {
+                stdout.write_all("…".as_bytes())?;
+                next_ln(stdout)?;
+                break;
+            }
+
+            stdout.write_all(line)?;
+            next_ln(stdout)?;
+        }
+
+        next_ln(stdout)?;
+        stdout.write_all(prompt.as_bytes())?;
+        stdout.write_all(b" (y/n)?")?;
+        stdout.queue(EndSynchronizedUpdate)?.flush()?;
+
+        let confirmed = loop {
+            if let Event::Key(key) = event::read().context("Failed to read terminal event")?
+                && key.kind == KeyEventKind::Press
+            {
+                match key.code {
+                    KeyCode::Char('y' | 'Y') => break true,
+                    KeyCode::Char('n' | 'N' | 'q') | KeyCode::Esc => break false,
+                    _ => (),
+                }
+            }
+        };
+
+        stdout.queue(Clear(ClearType::All))?;
+
+        Ok(confirmed)
+    }
+
+    // Show the changes which would be undone and ask for confirmation.
+    // No confirmation is needed if the exercise file wasn't changed.
+    fn confirm_reset(&self, stdout: &mut StdoutLock, exercise_ind: usize) -> Result<bool> {
+        let mut changes = Vec::with_capacity(1 << 12);
+        match self
+            .app_state
+            .write_exercise_changes(exercise_ind, &mut changes)
+        {
+            Ok(true) => (),
+            Ok(false) => return Ok(true),
+            Err(e) => {
+                changes.clear();
+                writeln!(changes, "Failed to show your changes: {e:#}")?;
+            }
         }
+
+        self.confirm(
+            stdout,
+            &changes,
+            &format!(
+                "Resetting will undo these changes to the file {}. Reset",
+                self.app_state.exercises()[exercise_ind].path,
+            ),
+        )
     }
 
-    pub fn reset_selected(&mut self) -> Result<()> {
+    fn reset_marked(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let mut content = Vec::with_capacity(1 << 12);
+        content
+            .extend_from_slice(b"Resetting will undo all your changes to the following files:\n");
+        for (exercise, _) in self
+            .app_state
+            .exercises()
+            .iter()
+            .zip(&self.marked)
+            .filter(|(_, marked)| **marked)
+        {
+            content.extend_from_slice(b"  ");
+            content.extend_from_slice(exercise.path.as_bytes());
+            content.push(b'\n');
+        }
+
+        if !self.confirm(stdout, &content, "Reset the marked exercises")? {
+            self.message.push_str("Reset cancelled");
+            return Ok(());
+        }
+
+        let mut n_changed = 0;
+        for exercise_ind in 0..self.marked.len() {
+            if !self.marked[exercise_ind] {
+                continue;
+            }
+
+            if self.app_state.reset_exercise_by_ind(exercise_ind)?.1 {
+                n_changed += 1;
+            }
+            self.marked[exercise_ind] = false;
+        }
+
+        self.message
+            .push_str("The marked exercises have been reset");
+        if n_changed > 0 {
+            self.message
+                .push_str(" │ Undo with `rustlings reset --undo <name>`");
+        }
+        self.update_rows();
+
+        Ok(())
+    }
+
+    // Reset the marked exercises or the selected one if none is marked.
+    pub fn reset_selected(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        if self.marked.contains(&true) {
+            return self.reset_marked(stdout);
+        }
+
         let Some(selected) = self.scroll_state.selected() else {
             self.message.push_str("Nothing selected to reset!");
             return Ok(());
         };
 
         let exercise_ind = self.selected_to_exercise_ind(selected)?;
-        let exercise_name = self.app_state.reset_exercise_by_ind(exercise_ind)?;
+        if !self.confirm_reset(stdout, exercise_ind)? {
+            self.message.push_str("Reset cancelled");
+            return Ok(());
+        }
+
+        let (exercise_name, changed) = self.app_state.reset_exercise_by_ind(exercise_ind)?;
         write!(
             self.message,
             "The exercise `{exercise_name}` has been reset",
         )?;
+        if changed {
+            write!(
+                self.message,
+                " │ Undo with `rustlings reset --undo {exercise_name}`",
+            )?;
+        }
         self.update_rows();
 
         Ok(())
     }
 
+    // Mark or unmark the selected exercise for resetting multiple exercises and select the next one.
+    pub fn toggle_mark_selected(&mut self) -> Result<()> {
+        let Some(selected) = self.scroll_state.selected() else {
+            self.message.push_str("Nothing selected to mark!");
+            return Ok(());
+        };
+
+        let exercise_ind = self.selected_to_exercise_ind(selected)?;
+        self.marked[exercise_ind] = !self.marked[exercise_ind];
+        self.scroll_state.select_next();
+
+        let n_marked = self.marked.iter().filter(|marked| **marked).count();
+        if n_marked > 0 {
+            write!(
+                self.message,
+                "{n_marked} marked │ Press {} to reset the marked exercises",
+                self.bindings.primary_label(ListAction::Reset),
+            )?;
+        }
+
+        Ok(())
+    }
+
     pub fn apply_search_query(&mut self) {
         self.message.push_str("search:");
         self.message.push_str(&self.search_query);
@@ -405,17 +640,12 @@ impl<'a> ListState<'a> {
             return;
         }
 
-        let is_search_result = |exercise: &Exercise| exercise.name.contains(&self.search_query);
-        let mut iter = self.app_state.exercises().iter();
-        let ind = match self.filter {
-            Filter::None => iter.position(is_search_result),
-            Filter::Done => iter
-                .filter(|exercise| exercise.done)
-                .position(is_search_result),
-            Filter::Pending => iter
-                .filter(|exercise| !exercise.done)
-                .position(is_search_result),
-        };
+        let ind = self
+            .app_state
+            .exercises()
+            .iter()
+            .filter(|exercise| self.filter.matches(exercise))
+            .position(|exercise| exercise.name.contains(&self.search_query));
 
         match ind {
             Some(exercise_ind) => self.scroll_state.set_selected(exercise_ind),
@@ -437,13 +667,42 @@ impl<'a> ListState<'a> {
     }
 }
 
-/// Draw an emphasized hotkey in the list footer.
-fn hotkey(writer: &mut MaxLenWriter, hotkey: &[u8]) -> io::Result<()> {
-    writer
-        .stdout
-        .queue(SetForegroundColor(Color::Yellow))?
-        .queue(SetAttribute(Attribute::Bold))?;
-    writer.write_ascii(hotkey)?;
+/// Draw the first key bound to an action emphasized in the list footer.
+fn hotkey(
+    writer: &mut MaxLenWriter,
+    bindings: &Bindings<ListAction>,
+    action: ListAction,
+) -> io::Result<()> {
+    set_style(writer.stdout, Style::Hotkey)?;
+    writer.stdout.queue(SetAttribute(Attribute::Bold))?;
+    writer.write_str(&bindings.primary_label(action))?;
     writer.stdout.queue(ResetColor)?;
     Ok(())
 }
+
+/// Draw the hotkey of a filter in the list footer followed by its name.
+/// The enabled filter is underlined.
+fn filter_hotkey(
+    writer: &mut MaxLenWriter,
+    bindings: &Bindings<ListAction>,
+    action: ListAction,
+    name: &[u8],
+    enabled: bool,
+) -> io::Result<()> {
+    if enabled {
+        writer.stdout.queue(SetAttribute(Attribute::Underlined))?;
+    }
+    hotkey(writer, bindings, action)?;
+    writer.write_ascii(b":")?;
+
+    if enabled {
+        set_style(writer.stdout, Style::Highlight)?;
+        writer.stdout.queue(SetAttribute(Attribute::Underlined))?;
+    }
+    writer.write_ascii(name)?;
+    if enabled {
+        writer.stdout.queue(ResetColor)?;
+    }
+
+    Ok(())
+}
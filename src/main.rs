@@ -10,31 +10,65 @@ use std::{
 use term::{clear_terminal, press_enter_prompt};
 
 use crate::{
-    app_state::AppState,
-    cli::{Args, Command},
+    app_state::{AppState, CheckAllOutcome},
+    cli::{Args, Command, OutputFormat},
+    config::Config,
     editor::Editor,
     info_file::InfoFile,
+    keymap::Keymap,
 };
 
 mod app_state;
+mod archive;
 mod cargo_toml;
+mod check_cache;
 mod cli;
 mod cmd;
+mod config;
 mod dev;
+mod diagnostics;
+mod diff;
 mod editor;
 mod embedded;
 mod exercise;
 mod info_file;
 mod init;
+mod json;
+mod keymap;
 mod list;
+mod notes;
+mod report;
+mod reset;
 mod run;
+mod state_file;
+mod stats;
 mod term;
+mod theme;
 mod watch;
 
 const CURRENT_FORMAT_VERSION: u8 = 1;
+// The exit code of `check-all --headless` on internal errors. Pending exercises result in 1.
+const HEADLESS_ERROR_EXIT_CODE: u8 = 2;
 
 fn main() -> Result<ExitCode> {
     let args = Args::parse();
+    if !matches!(args.command, Some(Command::CheckAll { headless: true, .. })) {
+        return rustlings(args);
+    }
+
+    // Scripts need to distinguish internal errors from pending exercises.
+    rustlings(args).or_else(|e| {
+        eprintln!("Error: {e:?}");
+        Ok(ExitCode::from(HEADLESS_ERROR_EXIT_CODE))
+    })
+}
+
+fn rustlings(args: Args) -> Result<ExitCode> {
+    let headless = matches!(args.command, Some(Command::CheckAll { headless: true, .. }));
+    let cli_config = Config::from_args(&args);
+    // The commands before loading the config only consider the theme from the command line.
+    theme::init(cli_config.theme);
+    cmd::forward_termination_signals()?;
 
     if cfg!(not(debug_assertions)) && Path::new("dev/rustlings-repo.txt").exists() {
         bail!("{OLD_METHOD_ERR}");
@@ -44,6 +78,7 @@ fn main() -> Result<ExitCode> {
         match args.command {
             Some(Command::Init) => init::init().context("Initialization failed")?,
             Some(Command::Dev(dev_command)) => dev_command.run()?,
+            Some(Command::Config(config_command)) => config_command.run(cli_config)?,
             _ => break 'priority_cmd,
         }
 
@@ -51,6 +86,10 @@ fn main() -> Result<ExitCode> {
     }
 
     if !Path::new("exercises").is_dir() {
+        if headless {
+            bail!("The `exercises/` directory couldn't be found in the current directory");
+        }
+
         println!("{PRE_INIT_MSG}");
         return Ok(ExitCode::FAILURE);
     }
@@ -61,22 +100,93 @@ fn main() -> Result<ExitCode> {
         bail!(FORMAT_VERSION_HIGHER_ERR);
     }
 
+    let mut config = config::load(cli_config)?;
+    let format = args.format.unwrap_or(OutputFormat::Text);
+    theme::init(config.theme);
+
     let vs_code_term = env::var_os("TERM_PROGRAM").is_some_and(|v| v == "vscode");
-    let editor = if args.no_editor {
+    let editor = if config.no_editor.unwrap_or_default() {
         None
     } else {
-        Editor::new(args.edit_cmd, vs_code_term)?
+        Editor::new(config.edit_cmd.take(), vs_code_term)?
     };
 
+    // Held until the end of `main`.
+    let state_lock = state_file::try_lock()?;
+    let read_only = state_lock.is_none() && !args.force;
+    // Read-only commands still work while another instance is running, but don't save anything.
+    if read_only
+        && !matches!(
+            args.command,
+            Some(
+                Command::Hint { .. }
+                    | Command::Diff { .. }
+                    | Command::Stats
+                    | Command::Export { .. }
+                    | Command::Notes(_)
+            ),
+        )
+    {
+        match state_file::lock_holder_pid() {
+            Some(pid) => {
+                bail!("Rustlings is already running in this directory, PID {pid}.\n{LOCKED_HINT}")
+            }
+            None => bail!("Rustlings is already running in this directory.\n{LOCKED_HINT}"),
+        }
+    }
+
     let (mut app_state, state_file_status) = AppState::new(
         info_file.exercises,
+        &info_file.tracks,
+        config.track.as_deref(),
         info_file.final_message.unwrap_or_default(),
         editor,
         vs_code_term,
+        read_only,
     )?;
 
+    // The watch mode renders the parsed diagnostics natively and JSON documents contain them.
+    let cmd_runner = app_state.cmd_runner_mut();
+    if args.command.is_none() || format == OutputFormat::Json {
+        cmd_runner.parse_diagnostics();
+    }
+    // Show the output of the watch mode and `run` while it arrives.
+    if matches!(args.command, None | Some(Command::Run { .. }))
+        && format == OutputFormat::Text
+        && io::stdout().is_terminal()
+    {
+        cmd_runner.stream_output();
+    }
+    if let Some(timeout) = config.timeout {
+        cmd_runner.set_timeout(timeout);
+    }
+    if let Some(output_limit) = config.output_limit {
+        cmd_runner.set_output_limit(output_limit);
+    }
+    if let Some(parallelism) = config.parallelism {
+        app_state.set_check_parallelism(parallelism);
+    }
+    // Results which others rely on (e.g. in CI or for grading) can't come from the cache
+    // because the learner can edit it.
+    if config.no_cache.unwrap_or_default()
+        || headless
+        || format == OutputFormat::Json
+        || matches!(
+            args.command,
+            Some(Command::CheckAll {
+                report: Some(_),
+                ..
+            })
+        )
+    {
+        app_state.disable_check_cache();
+    }
+
     // Show the welcome message if the state file doesn't exist yet.
-    if let Some(welcome_message) = info_file.welcome_message {
+    if let Some(welcome_message) = info_file.welcome_message
+        && format == OutputFormat::Text
+        && !headless
+    {
         match state_file_status {
             StateFileStatus::NotRead => {
                 let mut stdout = io::stdout().lock();
@@ -93,7 +203,19 @@ fn main() -> Result<ExitCode> {
                 // Flush to be able to show errors occurring before printing a newline to stdout.
                 stdout.flush()?;
             }
-            StateFileStatus::Read => (),
+            StateFileStatus::Read | StateFileStatus::Recovered => (),
+        }
+    }
+
+    if let StateFileStatus::Recovered = state_file_status {
+        // On stderr to keep JSON documents on stdout valid.
+        eprintln!("{STATE_FILE_RECOVERED_MSG}");
+
+        // The watch mode clears the terminal.
+        if args.command.is_none() {
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(b"\nPress ENTER to continue ")?;
+            press_enter_prompt(&mut stdout)?;
         }
     }
 
@@ -103,7 +225,7 @@ fn main() -> Result<ExitCode> {
                 bail!("Unsupported or missing terminal/TTY");
             }
 
-            let notify_exercise_names = if args.manual_run {
+            let notify_exercise_names = if config.manual_run.unwrap_or_default() {
                 None
             } else {
                 // For the notify event handler thread.
@@ -118,22 +240,85 @@ fn main() -> Result<ExitCode> {
                 )
             };
 
-            watch::watch(&mut app_state, notify_exercise_names)?;
+            let keymap = Keymap::new(&config.keymap).context(
+                "Invalid key bindings in the config files (see `rustlings config show`)",
+            )?;
+            // Leaking is fine since the keymap is used until the end of the program.
+            let keymap = Box::leak(Box::new(keymap));
+
+            watch::watch(&mut app_state, notify_exercise_names, keymap)?;
             app_state.close_editor()?;
         }
         Some(Command::Run { name }) => {
             if let Some(name) = name {
                 app_state.set_current_exercise_by_name(&name)?;
             }
-            return run::run(&mut app_state);
+            return run::run(&mut app_state, format);
         }
-        Some(Command::CheckAll) => {
+        Some(Command::CheckAll {
+            headless,
+            report,
+            report_format,
+        }) => {
             let mut stdout = io::stdout().lock();
-            if let Some(first_pending_exercise_ind) = app_state.check_all_exercises(&mut stdout)? {
-                if app_state.current_exercise().done {
-                    app_state.set_current_exercise_ind(first_pending_exercise_ind)?;
+            let outcome = match format {
+                OutputFormat::Text if headless => {
+                    app_state.check_all_exercises_lines(&mut stdout)?
+                }
+                OutputFormat::Text => app_state.check_all_exercises(&mut stdout)?,
+                OutputFormat::Json => app_state.check_all_exercises_headless()?,
+            };
+            let first_pending_exercise_ind = match outcome {
+                CheckAllOutcome::AllDone => None,
+                CheckAllOutcome::Pending(first_pending_exercise_ind) => {
+                    Some(first_pending_exercise_ind)
+                }
+                // Only possible in the watch mode.
+                CheckAllOutcome::Cancelled => bail!("Checking all exercises was cancelled"),
+            };
+
+            if let Some(first_pending_exercise_ind) = first_pending_exercise_ind
+                && app_state.current_exercise().done
+            {
+                app_state.set_current_exercise_ind(first_pending_exercise_ind)?;
+            }
+
+            if let Some(report_path) = report {
+                report::write(app_state.exercises(), report_format, &report_path)?;
+            }
+
+            if format == OutputFormat::Json {
+                json::print(&json::CheckAllDocument::new(
+                    &app_state,
+                    first_pending_exercise_ind.map(|_| app_state.current_exercise_ind()),
+                ))?;
+
+                if first_pending_exercise_ind.is_some() {
+                    return Ok(ExitCode::FAILURE);
                 }
 
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            if headless {
+                // Without terminal file links and the final message which clears the terminal.
+                if first_pending_exercise_ind.is_some() {
+                    writeln!(
+                        stdout,
+                        "\n{}/{} exercises pending. The first: {}",
+                        app_state.n_pending(),
+                        app_state.n_exercises(),
+                        app_state.current_exercise().path,
+                    )?;
+
+                    return Ok(ExitCode::FAILURE);
+                }
+
+                stdout.write_all("\nAll exercises done ✓\n".as_bytes())?;
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            if first_pending_exercise_ind.is_some() {
                 stdout.write_all(b"\n\n")?;
                 let pending = app_state.n_pending();
                 if pending == 1 {
@@ -142,7 +327,7 @@ fn main() -> Result<ExitCode> {
                     write!(
                         stdout,
                         "{pending}/{} exercises pending. The first: ",
-                        app_state.exercises().len(),
+                        app_state.n_exercises(),
                     )?;
                 }
                 app_state
@@ -155,32 +340,88 @@ fn main() -> Result<ExitCode> {
 
             app_state.render_final_message(&mut stdout)?;
         }
-        Some(Command::Reset { name }) => {
-            app_state.set_current_exercise_by_name(&name)?;
-            app_state.reset_current_exercise()?;
-
-            let current_exercise = app_state.current_exercise();
-            let mut stdout = io::stdout().lock();
-            stdout.write_all(b"The exercise ")?;
-            current_exercise.terminal_file_link(&mut stdout, app_state.emit_file_links())?;
-            stdout.write_all(b" has been reset\n")?;
+        Some(Command::Reset {
+            name,
+            dir,
+            all: _,
+            yes,
+            undo,
+        }) => {
+            if undo {
+                // Clap requires the name with `--undo`.
+                reset::undo(&mut app_state, name.as_deref().unwrap_or_default())?;
+            } else {
+                reset::reset(&mut app_state, name.as_deref(), dir.as_deref(), yes)?;
+            }
         }
-        Some(Command::Hint { name }) => {
+        Some(Command::Hint { name, level }) => {
             if let Some(name) = name {
                 app_state.set_current_exercise_by_name(&name)?;
             }
 
+            let level = level.unwrap_or_else(|| app_state.current_exercise().next_hint_level());
+            app_state.reveal_hint(level)?;
+            if read_only {
+                // On stderr to keep JSON documents on stdout valid.
+                eprintln!("{HINT_NOT_SAVED_MSG}");
+            }
             let current_exercise = app_state.current_exercise();
+            let hint = current_exercise.hints[level as usize - 1];
+            if format == OutputFormat::Json {
+                json::print(&json::HintDocument {
+                    exercise: current_exercise.into(),
+                    level,
+                    n_levels: current_exercise.n_hint_levels(),
+                    hint,
+                })?;
+
+                return Ok(ExitCode::SUCCESS);
+            }
+
             let mut stdout = io::stdout().lock();
             stdout.write_all(b"Current exercise: ")?;
             current_exercise.terminal_file_link(&mut stdout, app_state.emit_file_links())?;
 
-            stdout.write_all(b"\n\nHint:\n")?;
-            stdout.write_all(current_exercise.hint.as_bytes())?;
+            stdout.write_all(b"\n\n")?;
+            current_exercise.write_hint_heading(&mut stdout, level)?;
+            stdout.write_all(b":\n")?;
+            stdout.write_all(hint.as_bytes())?;
             stdout.write_all(b"\n")?;
         }
+        Some(Command::Diff { name }) => {
+            if let Some(name) = name {
+                app_state.set_current_exercise_by_name(&name)?;
+            }
+
+            let current_exercise = app_state.current_exercise();
+            if !current_exercise.done {
+                bail!(
+                    "The exercise `{}` isn't done yet. Compare it with its solution after solving it",
+                    current_exercise.name,
+                );
+            }
+
+            let Some(solution_path) = app_state.current_solution_path()? else {
+                bail!(
+                    "There is no solution for the exercise `{}`",
+                    current_exercise.name,
+                );
+            };
+
+            diff::diff_with_solution(
+                &mut io::stdout().lock(),
+                current_exercise.path,
+                &solution_path,
+            )?;
+        }
+        Some(Command::Stats) => stats::stats(&app_state, format)?,
+        Some(Command::Export { archive }) => archive::export(&app_state, &archive)?,
+        Some(Command::Import { archive, overwrite }) => {
+            archive::import(&mut app_state, &archive, overwrite)?;
+        }
+        Some(Command::Notes(notes_command)) => notes_command.run(&app_state)?,
         // Handled in an earlier match.
-        Some(Command::Init | Command::Dev(_)) => (),
+        Some(Command::Init | Command::Dev(_) | Command::Config(_)) => (),
     }
 
     Ok(ExitCode::SUCCESS)
@@ -197,6 +438,17 @@ const FORMAT_VERSION_HIGHER_ERR: &str =
 It is possible that you have an outdated version of Rustlings.
 Try to install the latest Rustlings version first.";
 
+const LOCKED_HINT: &str = "Close the other instance first.
+You can pass `--force` to run anyway, but the progress saved by one of the instances might be lost.";
+
+const HINT_NOT_SAVED_MSG: &str = "Rustlings is already running in this directory.
+The revealed hint level isn't saved to your progress.";
+
+const STATE_FILE_RECOVERED_MSG: &str =
+    "The state file `.rustlings-state.txt` was empty or invalid (e.g. because Rustlings was killed while writing it).
+Your progress was restored from the backup `.rustlings-state.txt.bak`.
+Only the last change to your progress might have been lost.";
+
 const PRE_INIT_MSG: &str = r"
        Welcome to...
                  _   _ _
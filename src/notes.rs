@@ -0,0 +1,106 @@
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crate::app_state::AppState;
+
+// The notes of every exercise are stored in a Markdown file named after the exercise.
+// Not in `.rustlings/` which is ignored by Git because the notes are written by the user.
+const NOTES_DIR: &str = "notes";
+
+#[derive(Subcommand)]
+pub enum NotesCommand {
+    /// Export the notes of all exercises into a single Markdown document grouped by topic
+    Export {
+        /// The path of the Markdown file to create. The document is printed if not specified
+        path: Option<PathBuf>,
+    },
+}
+
+impl NotesCommand {
+    pub fn run(self, app_state: &AppState) -> Result<()> {
+        match self {
+            Self::Export { path } => export(app_state, path),
+        }
+    }
+}
+
+pub fn notes_path(exercise_name: &str) -> String {
+    format!("{NOTES_DIR}/{exercise_name}.md")
+}
+
+/// Read the notes of an exercise. `None` if the notes file doesn't exist or contains only
+/// whitespace.
+pub fn read(exercise_name: &str) -> Result<Option<String>> {
+    let path = notes_path(exercise_name);
+    match fs::read_to_string(&path) {
+        Ok(notes) if notes.trim().is_empty() => Ok(None),
+        Ok(notes) => Ok(Some(notes)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read the notes file {path}")),
+    }
+}
+
+/// Whether notes were written for an exercise. See `read`.
+pub fn has_notes(exercise_name: &str) -> bool {
+    matches!(read(exercise_name), Ok(Some(_)))
+}
+
+/// Create an empty notes file for an exercise if it doesn't exist yet and return its path.
+pub fn create(exercise_name: &str) -> Result<String> {
+    let path = notes_path(exercise_name);
+    fs::create_dir_all(NOTES_DIR)
+        .with_context(|| format!("Failed to create the directory {NOTES_DIR}"))?;
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to create the notes file {path}"))?;
+
+    Ok(path)
+}
+
+fn export(app_state: &AppState, path: Option<PathBuf>) -> Result<()> {
+    let mut document = String::from("# Rustlings notes\n");
+    let mut n_notes = 0;
+    // The exercises of a topic are consecutive.
+    let mut last_dir = None;
+    for exercise in app_state.exercises() {
+        let Some(notes) = read(exercise.name)? else {
+            continue;
+        };
+
+        if n_notes == 0 || last_dir != exercise.dir {
+            document.push_str("\n## ");
+            document.push_str(exercise.dir.unwrap_or("other"));
+            document.push('\n');
+            last_dir = exercise.dir;
+        }
+
+        document.push_str("\n### ");
+        document.push_str(exercise.name);
+        document.push_str("\n\n");
+        document.push_str(notes.trim());
+        document.push('\n');
+        n_notes += 1;
+    }
+
+    let Some(path) = path else {
+        io::stdout().lock().write_all(document.as_bytes())?;
+        return Ok(());
+    };
+
+    fs::write(&path, document)
+        .with_context(|| format!("Failed to write the file {}", path.display()))?;
+    println!(
+        "Exported the notes of {n_notes} exercise{} to {}",
+        if n_notes == 1 { "" } else { "s" },
+        path.display(),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::{fmt::Write as _, fs, path::Path};
+
+use crate::exercise::Exercise;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    /// JUnit XML with one test case per exercise
+    Junit,
+    /// Test Anything Protocol with one test point per exercise
+    Tap,
+}
+
+// Escape a string for an XML attribute value.
+fn xml_escape(output: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            _ => output.push(c),
+        }
+    }
+}
+
+fn junit(exercises: &[&Exercise]) -> String {
+    let n_pending = exercises.iter().filter(|exercise| !exercise.done).count();
+    let mut report = String::with_capacity(128 * (exercises.len() + 1));
+    report.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    // Writing to a `String` can't fail.
+    let _ = writeln!(
+        report,
+        "<testsuites name=\"rustlings\" tests=\"{}\" failures=\"{n_pending}\">",
+        exercises.len(),
+    );
+    let _ = writeln!(
+        report,
+        "  <testsuite name=\"rustlings\" tests=\"{}\" failures=\"{n_pending}\">",
+        exercises.len(),
+    );
+
+    for exercise in exercises {
+        report.push_str("    <testcase name=\"");
+        xml_escape(&mut report, exercise.name);
+        report.push_str("\" classname=\"");
+        xml_escape(&mut report, exercise.dir.unwrap_or("other"));
+        report.push_str("\" file=\"");
+        xml_escape(&mut report, exercise.path);
+
+        if exercise.done {
+            report.push_str("\"/>\n");
+        } else {
+            report.push_str("\">\n");
+            report.push_str("      <failure message=\"The exercise is pending\"/>\n");
+            report.push_str("    </testcase>\n");
+        }
+    }
+
+    report.push_str("  </testsuite>\n</testsuites>\n");
+    report
+}
+
+fn tap(exercises: &[&Exercise]) -> String {
+    let mut report = String::with_capacity(64 * (exercises.len() + 1));
+    // Writing to a `String` can't fail.
+    let _ = write!(report, "TAP version 14\n1..{}\n", exercises.len());
+
+    for (ind, exercise) in exercises.iter().enumerate() {
+        if exercise.done {
+            let _ = writeln!(report, "ok {} - {}", ind + 1, exercise.name);
+        } else {
+            let _ = write!(
+                report,
+                "not ok {} - {}\n  ---\n  path: {}\n  ...\n",
+                ind + 1,
+                exercise.name,
+                exercise.path,
+            );
+        }
+    }
+
+    report
+}
+
+/// Write a report of the states of the exercises in the selected track after checking them.
+pub fn write(exercises: &[Exercise], format: ReportFormat, path: &Path) -> Result<()> {
+    let exercises = exercises
+        .iter()
+        .filter(|exercise| exercise.in_track)
+        .collect::<Vec<_>>();
+    let report = match format {
+        ReportFormat::Junit => junit(&exercises),
+        ReportFormat::Tap => tap(&exercises),
+    };
+
+    fs::write(path, report)
+        .with_context(|| format!("Failed to write the report {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_file::ExerciseHistory;
+
+    fn exercise(name: &'static str, in_track: bool, done: bool) -> Exercise {
+        Exercise {
+            name,
+            dir: None,
+            path: "exercises/test.rs",
+            canonical_path: None,
+            test: false,
+            strict_clippy: false,
+            timeout: None,
+            hints: vec![""],
+            prerequisites: Vec::new(),
+            in_track,
+            done,
+            skipped: false,
+            history: ExerciseHistory::default(),
+        }
+    }
+
+    #[test]
+    fn only_track() {
+        let exercises = [
+            exercise("a", true, true),
+            exercise("b", false, false),
+            exercise("c", true, false),
+        ];
+        let report_dir = tempfile::TempDir::new().unwrap();
+        let report_path = report_dir.path().join("report");
+
+        write(&exercises, ReportFormat::Tap, &report_path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&report_path).unwrap(),
+            "TAP version 14\n1..2\nok 1 - a\nnot ok 2 - c\n  ---\n  path: exercises/test.rs\n  ...\n",
+        );
+
+        write(&exercises, ReportFormat::Junit, &report_path).unwrap();
+        let report = fs::read_to_string(&report_path).unwrap();
+        assert!(report.contains("<testsuites name=\"rustlings\" tests=\"2\" failures=\"1\">"));
+        assert!(!report.contains("name=\"b\""));
+    }
+}
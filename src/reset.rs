@@ -0,0 +1,181 @@
+use anyhow::{Context, Result, bail};
+use std::io::{self, StdoutLock, Write};
+
+use crate::app_state::AppState;
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+// `*` matches any sequence of characters and `?` matches a single character.
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let name = name.as_bytes();
+    let (mut pattern_ind, mut name_ind) = (0, 0);
+    // The index of the last `*` in the pattern and the index in the name that it matched until.
+    let mut backtrack = None;
+
+    while name_ind < name.len() {
+        match pattern.get(pattern_ind) {
+            Some(b'*') => {
+                backtrack = Some((pattern_ind, name_ind));
+                pattern_ind += 1;
+            }
+            Some(&c) if c == b'?' || c == name[name_ind] => {
+                pattern_ind += 1;
+                name_ind += 1;
+            }
+            _ => {
+                // Let the last `*` match one more character.
+                let Some((star_ind, star_name_ind)) = backtrack else {
+                    return false;
+                };
+                backtrack = Some((star_ind, star_name_ind + 1));
+                pattern_ind = star_ind + 1;
+                name_ind = star_name_ind + 1;
+            }
+        }
+    }
+
+    pattern[pattern_ind..].iter().all(|c| *c == b'*')
+}
+
+fn confirm(stdout: &mut StdoutLock) -> Result<bool> {
+    stdout.write_all(b"\nReset these exercises (y/n)? ")?;
+    stdout.flush()?;
+
+    let mut answer = String::with_capacity(8);
+    io::stdin()
+        .read_line(&mut answer)
+        .context("Failed to read the answer from stdin")?;
+
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes",
+    ))
+}
+
+fn reset_exercise(app_state: &mut AppState, name: &str) -> Result<()> {
+    app_state.set_current_exercise_by_name(name)?;
+    let changed = app_state.reset_current_exercise()?;
+
+    let current_exercise = app_state.current_exercise();
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(b"The exercise ")?;
+    current_exercise.terminal_file_link(&mut stdout, app_state.emit_file_links())?;
+    stdout.write_all(b" has been reset\n")?;
+    if changed {
+        writeln!(
+            stdout,
+            "Undo with `rustlings reset --undo {}`",
+            current_exercise.name,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Reset the exercise with the given name or all exercises matching a glob pattern like
+/// `iterators*`, all exercises in the directory `dir` or all exercises if neither is given.
+/// Asks for confirmation before resetting multiple exercises unless `yes` is `true`.
+pub fn reset(
+    app_state: &mut AppState,
+    name: Option<&str>,
+    dir: Option<&str>,
+    yes: bool,
+) -> Result<()> {
+    if let Some(name) = name
+        && !is_glob(name)
+    {
+        return reset_exercise(app_state, name);
+    }
+
+    // Allow `exercises/06_move_semantics/` too.
+    let dir = dir.map(|dir| {
+        let dir = dir.trim_end_matches(['/', '\\']);
+        dir.strip_prefix("exercises/")
+            .or_else(|| dir.strip_prefix("exercises\\"))
+            .unwrap_or(dir)
+    });
+    let exercise_inds = app_state
+        .exercises()
+        .iter()
+        .enumerate()
+        .filter(|(_, exercise)| match (name, dir) {
+            (Some(pattern), _) => matches_glob(pattern, exercise.name),
+            (None, Some(dir)) => exercise.dir == Some(dir),
+            (None, None) => true,
+        })
+        .map(|(ind, _)| ind)
+        .collect::<Vec<_>>();
+
+    if exercise_inds.is_empty() {
+        match (name, dir) {
+            (Some(pattern), _) => bail!("No exercise matches the pattern '{pattern}'"),
+            (None, Some(dir)) => bail!("No exercises found in the directory '{dir}'"),
+            (None, None) => bail!("There are no exercises to reset"),
+        }
+    }
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(b"Resetting will undo all your changes to the following files:\n")?;
+    for &exercise_ind in &exercise_inds {
+        stdout.write_all(b"  ")?;
+        app_state.exercises()[exercise_ind]
+            .terminal_file_link(&mut stdout, app_state.emit_file_links())?;
+        stdout.write_all(b"\n")?;
+    }
+
+    if !yes && !confirm(&mut stdout)? {
+        stdout.write_all(b"Reset cancelled\n")?;
+        return Ok(());
+    }
+
+    let mut n_changed = 0;
+    for exercise_ind in exercise_inds {
+        if app_state.reset_exercise_by_ind(exercise_ind)?.1 {
+            n_changed += 1;
+        }
+    }
+
+    stdout.write_all(b"\nThe exercises have been reset\n")?;
+    if n_changed > 0 {
+        stdout
+            .write_all(b"Undo the reset of an exercise with `rustlings reset --undo <name>`\n")?;
+    }
+
+    Ok(())
+}
+
+pub fn undo(app_state: &mut AppState, name: &str) -> Result<()> {
+    app_state.set_current_exercise_by_name(name)?;
+    app_state.undo_reset_current_exercise()?;
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(b"The exercise ")?;
+    app_state
+        .current_exercise()
+        .terminal_file_link(&mut stdout, app_state.emit_file_links())?;
+    stdout.write_all(b" has been restored to its version before the last reset\n")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        assert!(matches_glob("iterators*", "iterators1"));
+        assert!(matches_glob("iterators*", "iterators"));
+        assert!(!matches_glob("iterators*", "move_semantics1"));
+        assert!(matches_glob("*3", "structs3"));
+        assert!(!matches_glob("*3", "structs3x"));
+        assert!(matches_glob("v?cs*", "vecs2"));
+        assert!(matches_glob("*_*_*", "a_b_c"));
+        assert!(!matches_glob("*_*_*", "a_bc"));
+        assert!(matches_glob("*", ""));
+        assert!(!matches_glob("?", ""));
+    }
+}
@@ -1,8 +1,5 @@
 use anyhow::Result;
-use crossterm::{
-    QueueableCommand,
-    style::{Color, ResetColor, SetForegroundColor},
-};
+use crossterm::{QueueableCommand, style::ResetColor};
 use std::{
     io::{self, Write},
     process::ExitCode,
@@ -10,16 +7,67 @@ use std::{
 
 use crate::{
     app_state::{AppState, ExercisesProgress},
-    exercise::{OUTPUT_CAPACITY, RunnableExercise, solution_link_line},
+    cli::OutputFormat,
+    exercise::{OUTPUT_CAPACITY, RunOutcome, RunnableExercise, solution_link_line},
+    json, term,
+    theme::{Style, set_style},
 };
 
-pub fn run(app_state: &mut AppState) -> Result<ExitCode> {
-    let exercise = app_state.current_exercise();
+fn run_json(app_state: &mut AppState, outcome: &RunOutcome, output: &[u8]) -> Result<ExitCode> {
+    let exercise_ind = app_state.current_exercise_ind();
+    let success = outcome.success();
+
+    let next_pending_exercise_ind = if success {
+        match app_state.done_current_exercise::<false>(None)? {
+            ExercisesProgress::NewPending | ExercisesProgress::CurrentPending => {
+                Some(app_state.current_exercise_ind())
+            }
+            ExercisesProgress::AllDone => None,
+        }
+    } else {
+        app_state.set_pending(exercise_ind)?;
+        Some(exercise_ind)
+    };
+
+    let exercises = app_state.exercises();
+    let exercise = &exercises[exercise_ind];
+    json::print(&json::RunDocument {
+        exercise: exercise.into(),
+        success,
+        stages: outcome
+            .stages
+            .iter()
+            .map(|stage_outcome| json::StageDocument::new(stage_outcome, output))
+            .collect(),
+        diagnostics: &outcome.diagnostics,
+        output: term::strip_ansi(output),
+        hints: &exercise.hints[..(exercise.history.hint_level as usize).min(exercise.hints.len())],
+        next_pending: next_pending_exercise_ind.map(|ind| (&exercises[ind]).into()),
+    })?;
+
+    if success {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+pub fn run(app_state: &mut AppState, format: OutputFormat) -> Result<ExitCode> {
     let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
-    let success = exercise.run_exercise(Some(&mut output), app_state.cmd_runner())?;
+    let outcome = app_state
+        .current_exercise()
+        .run_exercise(Some(&mut output), app_state.cmd_runner())?;
+    app_state.record_run()?;
 
+    if format == OutputFormat::Json {
+        return run_json(app_state, &outcome, &output);
+    }
+
+    let success = outcome.success();
     let mut stdout = io::stdout().lock();
-    stdout.write_all(&output)?;
+    if !app_state.cmd_runner().streams_output() {
+        stdout.write_all(&output)?;
+    }
 
     if !success {
         app_state.set_pending(app_state.current_exercise_ind())?;
@@ -33,9 +81,9 @@ pub fn run(app_state: &mut AppState) -> Result<ExitCode> {
         return Ok(ExitCode::FAILURE);
     }
 
-    stdout.queue(SetForegroundColor(Color::Green))?;
+    set_style(&mut stdout, Style::Success)?;
     stdout.write_all("✓ Successfully ran ".as_bytes())?;
-    stdout.write_all(exercise.path.as_bytes())?;
+    stdout.write_all(app_state.current_exercise().path.as_bytes())?;
     stdout.queue(ResetColor)?;
     stdout.write_all(b"\n")?;
 
@@ -45,7 +93,7 @@ pub fn run(app_state: &mut AppState) -> Result<ExitCode> {
         stdout.write_all(b"\n")?;
     }
 
-    match app_state.done_current_exercise::<false>(&mut stdout)? {
+    match app_state.done_current_exercise::<false>(Some(&mut stdout))? {
         ExercisesProgress::NewPending | ExercisesProgress::CurrentPending => {
             stdout.write_all(b"Next exercise: ")?;
             app_state
@@ -0,0 +1,290 @@
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{self, Read, Write},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const STATE_FILE_NAME: &str = ".rustlings-state.txt";
+/// The previous version of the state file.
+pub const BACKUP_FILE_NAME: &str = ".rustlings-state.txt.bak";
+// Written completely before being renamed to the state file.
+const TMP_FILE_NAME: &str = ".rustlings-state.txt.tmp";
+// Locked by the Rustlings instance which is allowed to write the state file.
+// The state file itself can't be locked because it is replaced on every write.
+const LOCK_FILE_NAME: &str = ".rustlings-state.txt.lock";
+// The old plain text format is considered as version 1.
+const FORMAT_VERSION: u8 = 2;
+
+/// The current UNIX timestamp in seconds.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Read a file into the buffer after clearing it.
+/// Returns `false` if the file doesn't exist or can't be read.
+pub fn read(path: &str, buf: &mut Vec<u8>) -> bool {
+    buf.clear();
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(buf))
+        .is_ok()
+}
+
+/// Replace the state file without ever leaving a partially written one behind (e.g. if the
+/// process is killed). The previous state file is kept as a backup.
+pub fn save(content: &[u8]) -> Result<()> {
+    if let Err(e) = fs::copy(STATE_FILE_NAME, BACKUP_FILE_NAME)
+        && e.kind() != io::ErrorKind::NotFound
+    {
+        return Err(e).with_context(|| {
+            format!("Failed to back up the state file {STATE_FILE_NAME} to {BACKUP_FILE_NAME}")
+        });
+    }
+
+    let mut tmp_file = File::create(TMP_FILE_NAME)
+        .with_context(|| format!("Failed to create the temporary state file {TMP_FILE_NAME}"))?;
+    tmp_file
+        .write_all(content)
+        .and_then(|()| tmp_file.sync_all())
+        .with_context(|| format!("Failed to write the temporary state file {TMP_FILE_NAME}"))?;
+    drop(tmp_file);
+
+    fs::rename(TMP_FILE_NAME, STATE_FILE_NAME).with_context(|| {
+        format!("Failed to rename {TMP_FILE_NAME} to the state file {STATE_FILE_NAME}")
+    })
+}
+
+/// Try to take the advisory lock for writing the state file.
+/// The lock is held until the returned file is dropped or the process exits (even if killed).
+/// Returns `None` if another process holds the lock.
+pub fn try_lock() -> Result<Option<File>> {
+    let mut lock_file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .write(true)
+        .truncate(false)
+        .open(LOCK_FILE_NAME)
+        .with_context(|| format!("Failed to open or create the lock file {LOCK_FILE_NAME}"))?;
+
+    match lock_file.try_lock() {
+        Ok(()) => (),
+        Err(TryLockError::WouldBlock) => return Ok(None),
+        Err(TryLockError::Error(e)) => {
+            return Err(e).with_context(|| format!("Failed to lock the file {LOCK_FILE_NAME}"));
+        }
+    }
+
+    // For the error message of other instances.
+    lock_file
+        .set_len(0)
+        .and_then(|()| write!(lock_file, "{}", process::id()))
+        .with_context(|| format!("Failed to write the lock file {LOCK_FILE_NAME}"))?;
+
+    Ok(Some(lock_file))
+}
+
+/// The ID of the process holding the lock.
+/// `None` if it can't be read (e.g. because the locked file can't be read on Windows).
+pub fn lock_holder_pid() -> Option<u32> {
+    fs::read_to_string(LOCK_FILE_NAME).ok()?.parse().ok()
+}
+
+/// The learning history of an exercise.
+/// Timestamps are UNIX timestamps in seconds.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ExerciseHistory {
+    /// When the exercise was run for the first time.
+    pub started_at: Option<u64>,
+    /// When the exercise was done for the first time.
+    pub finished_at: Option<u64>,
+    /// How often the exercise was run (without checking all exercises).
+    #[serde(default)]
+    pub runs: u32,
+    #[serde(default)]
+    pub hints_viewed: u32,
+    /// The highest hint level revealed (starting at 1). `0` if no hint was revealed.
+    #[serde(default)]
+    pub hint_level: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExerciseState<'a> {
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub done: bool,
+    /// Skipped by the user while pending to come back to it later.
+    #[serde(default)]
+    pub skipped: bool,
+    #[serde(flatten)]
+    pub history: ExerciseHistory,
+}
+
+// Only the format version of a state file to detect newer formats before parsing them.
+#[derive(Deserialize)]
+struct FormatVersion {
+    format_version: u8,
+}
+
+/// Fail if the state was saved by a newer version of Rustlings.
+/// Treating it as invalid would overwrite the progress on the next write.
+pub fn check_format_version(format_version: u8) -> Result<()> {
+    if format_version > FORMAT_VERSION {
+        bail!(
+            "The progress was saved by a newer version of Rustlings. Update Rustlings to continue"
+        );
+    }
+
+    Ok(())
+}
+
+/// Like `check_format_version` for the content of a state file.
+pub fn check_content_format_version(content: &[u8]) -> Result<()> {
+    if content.first() == Some(&b'{')
+        && let Ok(format_version) = serde_json::de::from_slice::<FormatVersion>(content)
+    {
+        check_format_version(format_version.format_version)?;
+    }
+
+    Ok(())
+}
+
+/// The (de)serialized state file.
+#[derive(Serialize, Deserialize)]
+pub struct StateFile<'a> {
+    pub format_version: u8,
+    #[serde(borrow)]
+    pub current_exercise: Cow<'a, str>,
+    /// In the order of the exercises in `info.toml`.
+    #[serde(borrow)]
+    pub exercises: Vec<ExerciseState<'a>>,
+    /// The name of the selected track. `None` if all exercises are selected.
+    #[serde(default, borrow, skip_serializing_if = "Option::is_none")]
+    pub track: Option<Cow<'a, str>>,
+}
+
+impl<'a> StateFile<'a> {
+    pub fn new(current_exercise: &'a str, exercises: Vec<ExerciseState<'a>>) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            current_exercise: Cow::Borrowed(current_exercise),
+            exercises,
+            track: None,
+        }
+    }
+
+    /// Parse the state file in the current format or migrate it from the old plain text format.
+    /// Returns the state and whether it was migrated.
+    /// `None` if the file is empty or invalid.
+    pub fn parse(content: &'a [u8]) -> Option<(Self, bool)> {
+        if content.first() == Some(&b'{') {
+            let state_file = serde_json::de::from_slice::<Self>(content).ok()?;
+            if state_file.format_version > FORMAT_VERSION || state_file.current_exercise.is_empty()
+            {
+                return None;
+            }
+
+            return Some((state_file, false));
+        }
+
+        Self::parse_plain_text(content).map(|state_file| (state_file, true))
+    }
+
+    // The old plain text format:
+    // - The first line is a comment.
+    // - The second line is an empty line.
+    // - The third line is the name of the current exercise. It must end with `\n` even if there
+    // are no done exercises.
+    // - The fourth line is an empty line.
+    // - All remaining lines are the names of done exercises.
+    fn parse_plain_text(content: &'a [u8]) -> Option<Self> {
+        let content = str::from_utf8(content).ok()?;
+        let mut lines = content.split('\n').skip(2);
+
+        let current_exercise = lines.next()?;
+        if current_exercise.is_empty() || lines.next().is_none() {
+            return None;
+        }
+
+        let exercises = lines
+            .take_while(|name| !name.is_empty())
+            .map(|name| ExerciseState {
+                name: Cow::Borrowed(name),
+                done: true,
+                skipped: false,
+                history: ExerciseHistory::default(),
+            })
+            .collect();
+
+        Some(Self::new(current_exercise, exercises))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_plain_text() {
+        let (state_file, migrated) =
+            StateFile::parse(b"DON'T EDIT THIS FILE!\n\nb\n\na\nc").unwrap();
+        assert!(migrated);
+        assert_eq!(state_file.current_exercise, "b");
+        let done = state_file
+            .exercises
+            .iter()
+            .map(|exercise| (exercise.name.as_ref(), exercise.done))
+            .collect::<Vec<_>>();
+        assert_eq!(done, [("a", true), ("c", true)]);
+
+        assert!(StateFile::parse(b"").is_none());
+        assert!(StateFile::parse(b"DON'T EDIT THIS FILE!\n\nb").is_none());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let state_file = StateFile::new(
+            "a",
+            vec![ExerciseState {
+                name: Cow::Borrowed("a"),
+                done: false,
+                skipped: true,
+                history: ExerciseHistory {
+                    started_at: Some(1),
+                    finished_at: None,
+                    runs: 3,
+                    hints_viewed: 1,
+                    hint_level: 1,
+                },
+            }],
+        );
+        let json = serde_json::ser::to_vec(&state_file).unwrap();
+
+        let (state_file, migrated) = StateFile::parse(&json).unwrap();
+        assert!(!migrated);
+        assert_eq!(state_file.current_exercise, "a");
+        let exercise = &state_file.exercises[0];
+        assert!(exercise.skipped);
+        assert_eq!(exercise.history.started_at, Some(1));
+        assert_eq!(exercise.history.runs, 3);
+        assert_eq!(exercise.history.hints_viewed, 1);
+    }
+
+    #[test]
+    fn newer_format_version() {
+        let newer = format!(
+            r#"{{"format_version":{},"exercises":[]}}"#,
+            FORMAT_VERSION + 1
+        );
+        assert!(check_content_format_version(newer.as_bytes()).is_err());
+
+        let current = format!(r#"{{"format_version":{FORMAT_VERSION}}}"#);
+        assert!(check_content_format_version(current.as_bytes()).is_ok());
+        assert!(check_content_format_version(b"DON'T EDIT THIS FILE!\n\na").is_ok());
+        assert!(check_content_format_version(b"{invalid").is_ok());
+    }
+}
@@ -0,0 +1,124 @@
+use anyhow::Result;
+use crossterm::{
+    QueueableCommand,
+    style::{Attribute, ResetColor, SetAttribute},
+};
+use std::{
+    cmp::Reverse,
+    io::{self, StdoutLock, Write},
+};
+
+use crate::{
+    app_state::AppState,
+    cli::OutputFormat,
+    json::{self, StatsDocument},
+    theme::{Style, set_style},
+};
+
+// The number of exercises shown in the list of the most runs.
+const N_MOST_RUNS: usize = 5;
+
+fn heading(stdout: &mut StdoutLock, heading: &str) -> io::Result<()> {
+    stdout.queue(SetAttribute(Attribute::Bold))?;
+    stdout.write_all(heading.as_bytes())?;
+    stdout.queue(ResetColor)?;
+    stdout.write_all(b"\n")
+}
+
+// Like `2h 5m`, `12m` or `40s`.
+fn write_duration(stdout: &mut StdoutLock, secs: u64) -> io::Result<()> {
+    let hours = secs / 3600;
+    let minutes = secs % 3600 / 60;
+
+    if hours > 0 {
+        write!(stdout, "{hours}h {minutes}m")
+    } else if minutes > 0 {
+        write!(stdout, "{minutes}m")
+    } else {
+        write!(stdout, "{secs}s")
+    }
+}
+
+fn render(stdout: &mut StdoutLock, stats: &StatsDocument) -> io::Result<()> {
+    write!(
+        stdout,
+        "Progress: {}/{} exercises done",
+        stats.n_done, stats.n_exercises,
+    )?;
+    if let Some(track) = stats.track {
+        write!(stdout, " in the track `{track}`")?;
+    }
+    stdout.write_all(b"\n\n")?;
+
+    heading(stdout, "Topics")?;
+    let dir_width = stats
+        .topics
+        .iter()
+        .map(|topic| topic.dir.map_or(0, str::len))
+        .max()
+        .unwrap_or(0)
+        // 5 = "other".len()
+        .max(5);
+    for topic in &stats.topics {
+        let dir = topic.dir.unwrap_or("other");
+        write!(stdout, "  {dir:dir_width$}  ")?;
+        if topic.n_done == topic.n_exercises {
+            set_style(stdout, Style::Success)?;
+        }
+        write!(stdout, "{}/{}", topic.n_done, topic.n_exercises)?;
+        stdout.queue(ResetColor)?;
+        stdout.write_all(b"\n")?;
+    }
+
+    stdout.write_all(b"\n")?;
+    heading(stdout, "Time to solve")?;
+    stdout.write_all(b"  ")?;
+    write_duration(stdout, stats.time_to_solve_secs)?;
+    stdout.write_all(b" from the first run until done (summed over done exercises)\n\n")?;
+
+    heading(stdout, "Most runs")?;
+    let mut most_runs = stats
+        .exercises
+        .iter()
+        .filter(|exercise| exercise.history.runs > 0)
+        .collect::<Vec<_>>();
+    // Stable to keep the order of exercises with the same number of runs.
+    most_runs.sort_by_key(|exercise| Reverse(exercise.history.runs));
+    if most_runs.is_empty() {
+        stdout.write_all(b"  No runs yet\n")?;
+    }
+    let name_width = most_runs
+        .iter()
+        .take(N_MOST_RUNS)
+        .map(|exercise| exercise.exercise.name.len())
+        .max()
+        .unwrap_or(0);
+    for exercise in most_runs.into_iter().take(N_MOST_RUNS) {
+        let runs = exercise.history.runs;
+        writeln!(
+            stdout,
+            "  {:name_width$}  {runs} {}",
+            exercise.exercise.name,
+            if runs == 1 { "run" } else { "runs" },
+        )?;
+    }
+
+    stdout.write_all(b"\n")?;
+    heading(stdout, "Hints")?;
+    writeln!(
+        stdout,
+        "  Viewed {} times for {}/{} exercises",
+        stats.n_hints_viewed, stats.n_exercises_with_hints_viewed, stats.n_exercises,
+    )
+}
+
+pub fn stats(app_state: &AppState, format: OutputFormat) -> Result<()> {
+    let stats = StatsDocument::new(app_state);
+
+    match format {
+        OutputFormat::Text => render(&mut io::stdout().lock(), &stats)?,
+        OutputFormat::Json => json::print(&stats)?,
+    }
+
+    Ok(())
+}
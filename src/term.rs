@@ -1,7 +1,7 @@
 use crossterm::{
     Command, QueueableCommand,
-    cursor::MoveTo,
-    style::{Attribute, Color, ResetColor, SetAttribute, SetForegroundColor},
+    cursor::{MoveTo, MoveToColumn, MoveToPreviousLine},
+    style::{Attribute, ResetColor, SetAttribute},
     terminal::{Clear, ClearType},
 };
 use std::{
@@ -9,7 +9,10 @@ use std::{
     io::{self, BufRead, StdoutLock, Write},
 };
 
-use crate::app_state::CheckProgress;
+use crate::{
+    app_state::CheckProgress,
+    theme::{self, Style, Theme, set_style, unset_style},
+};
 
 pub struct MaxLenWriter<'a, 'lock> {
     pub stdout: &'a mut StdoutLock<'lock>,
@@ -84,59 +87,90 @@ impl<'a> CountedWrite<'a> for StdoutLock<'a> {
 pub struct CheckProgressVisualizer<'a, 'lock> {
     stdout: &'a mut StdoutLock<'lock>,
     n_cols: usize,
+    // Mark the progress with a symbol after the exercise number instead of only styling it.
+    symbols: bool,
 }
 
 impl<'a, 'lock> CheckProgressVisualizer<'a, 'lock> {
-    const CHECKING_COLOR: Color = Color::Blue;
-    const DONE_COLOR: Color = Color::Green;
-    const PENDING_COLOR: Color = Color::Red;
+    const CHECKING_STYLE: Style = Style::Active;
+    const DONE_STYLE: Style = Style::Success;
+    const PENDING_STYLE: Style = Style::Failure;
+
+    fn symbol(progress: CheckProgress) -> &'static str {
+        match progress {
+            CheckProgress::None => " ",
+            CheckProgress::Checking => "…",
+            CheckProgress::Done => "✓",
+            CheckProgress::Pending => "✗",
+        }
+    }
+
+    fn write_legend_entry(&mut self, progress: CheckProgress, style: Style) -> io::Result<()> {
+        set_style(self.stdout, style)?;
+        self.stdout.write_all(match progress {
+            CheckProgress::Checking => b"Checking",
+            CheckProgress::Done => b"Done",
+            _ => b"Pending",
+        })?;
+        self.stdout.queue(ResetColor)?;
+
+        if self.symbols {
+            self.stdout.write_all(b" ")?;
+            self.stdout.write_all(Self::symbol(progress).as_bytes())?;
+        }
+
+        Ok(())
+    }
 
     pub fn build(stdout: &'a mut StdoutLock<'lock>, term_width: u16) -> io::Result<Self> {
         clear_terminal(stdout)?;
         stdout.write_all("Checking all exercises…\n".as_bytes())?;
 
-        // Legend
-        stdout.write_all(b"Color of exercise number: ")?;
-        stdout.queue(SetForegroundColor(Self::CHECKING_COLOR))?;
-        stdout.write_all(b"Checking")?;
-        stdout.queue(ResetColor)?;
-        stdout.write_all(b" - ")?;
-        stdout.queue(SetForegroundColor(Self::DONE_COLOR))?;
-        stdout.write_all(b"Done")?;
-        stdout.queue(ResetColor)?;
-        stdout.write_all(b" - ")?;
-        stdout.queue(SetForegroundColor(Self::PENDING_COLOR))?;
-        stdout.write_all(b"Pending")?;
-        stdout.queue(ResetColor)?;
-        stdout.write_all(b"\n")?;
-
-        // Exercise numbers with up to 3 digits.
+        let symbols = theme::theme() == Theme::Mono;
+        // Exercise numbers with up to 3 digits (and a symbol).
         // +1 because the last column doesn't end with a whitespace.
-        let n_cols = usize::from(term_width + 1) / 4;
+        let col_width = if symbols { 5 } else { 4 };
+        let n_cols = usize::from(term_width + 1) / col_width;
+
+        let mut slf = Self {
+            stdout,
+            n_cols,
+            symbols,
+        };
 
-        Ok(Self { stdout, n_cols })
+        // Legend
+        if symbols {
+            slf.stdout.write_all(b"Exercise number followed by: ")?;
+        } else {
+            slf.stdout.write_all(b"Color of exercise number: ")?;
+        }
+        slf.write_legend_entry(CheckProgress::Checking, Self::CHECKING_STYLE)?;
+        slf.stdout.write_all(b" - ")?;
+        slf.write_legend_entry(CheckProgress::Done, Self::DONE_STYLE)?;
+        slf.stdout.write_all(b" - ")?;
+        slf.write_legend_entry(CheckProgress::Pending, Self::PENDING_STYLE)?;
+        slf.stdout.write_all(b"\n")?;
+
+        Ok(slf)
     }
 
     pub fn update(&mut self, progresses: &[CheckProgress]) -> io::Result<()> {
         self.stdout.queue(MoveTo(0, 2))?;
 
         let mut exercise_num = 1;
-        for exercise_progress in progresses {
+        for &exercise_progress in progresses {
             match exercise_progress {
                 CheckProgress::None => (),
-                CheckProgress::Checking => {
-                    self.stdout
-                        .queue(SetForegroundColor(Self::CHECKING_COLOR))?;
-                }
-                CheckProgress::Done => {
-                    self.stdout.queue(SetForegroundColor(Self::DONE_COLOR))?;
-                }
-                CheckProgress::Pending => {
-                    self.stdout.queue(SetForegroundColor(Self::PENDING_COLOR))?;
-                }
+                CheckProgress::Checking => set_style(self.stdout, Self::CHECKING_STYLE)?,
+                CheckProgress::Done => set_style(self.stdout, Self::DONE_STYLE)?,
+                CheckProgress::Pending => set_style(self.stdout, Self::PENDING_STYLE)?,
             }
 
             write!(self.stdout, "{exercise_num:<3}")?;
+            if self.symbols {
+                self.stdout
+                    .write_all(Self::symbol(exercise_progress).as_bytes())?;
+            }
             self.stdout.queue(ResetColor)?;
 
             if exercise_num != progresses.len() {
@@ -154,6 +188,48 @@ impl<'a, 'lock> CheckProgressVisualizer<'a, 'lock> {
     }
 }
 
+/// Prints one line per checked exercise without using terminal features.
+/// The lines are printed in the order of the exercises.
+pub struct CheckProgressLines<'a, 'lock> {
+    stdout: &'a mut StdoutLock<'lock>,
+    exercise_names: Vec<&'static str>,
+    n_printed: usize,
+}
+
+impl<'a, 'lock> CheckProgressLines<'a, 'lock> {
+    pub fn new(stdout: &'a mut StdoutLock<'lock>, exercise_names: Vec<&'static str>) -> Self {
+        Self {
+            stdout,
+            exercise_names,
+            n_printed: 0,
+        }
+    }
+
+    pub fn update(&mut self, progresses: &[CheckProgress]) -> io::Result<()> {
+        let total = progresses.len();
+        let num_width = total.to_string().len();
+
+        while let Some(&progress) = progresses.get(self.n_printed) {
+            let state = match progress {
+                CheckProgress::Done => "done",
+                CheckProgress::Pending => "pending",
+                // Wait for the exercise to be checked.
+                CheckProgress::None | CheckProgress::Checking => break,
+            };
+
+            self.n_printed += 1;
+            writeln!(
+                self.stdout,
+                "{:>num_width$}/{total} {state:<7} {}",
+                self.n_printed,
+                self.exercise_names[self.n_printed - 1],
+            )?;
+        }
+
+        self.stdout.flush()
+    }
+}
+
 pub struct ProgressCounter<'a, 'lock> {
     stdout: &'a mut StdoutLock<'lock>,
     total: usize,
@@ -213,7 +289,7 @@ pub fn progress_bar<'a>(
     let width = u32::from(term_width - WRAPPER_WIDTH);
     let filled = (width * progress) / total;
 
-    stdout.queue(SetForegroundColor(Color::Green))?;
+    set_style(stdout, Style::Success)?;
     for _ in 0..filled {
         stdout.write_all(b"#")?;
     }
@@ -223,14 +299,15 @@ pub fn progress_bar<'a>(
 
         let width_minus_filled = width - filled;
         if width_minus_filled > 1 {
-            stdout.queue(SetForegroundColor(Color::Red))?;
+            unset_style(stdout, Style::Success)?;
+            set_style(stdout, Style::Failure)?;
             for _ in 1..width_minus_filled {
                 stdout.write_all(b"-")?;
             }
         }
     }
 
-    stdout.queue(SetForegroundColor(Color::Reset))?;
+    stdout.queue(ResetColor)?;
 
     write!(stdout, "] {progress:>3}/{total}")
 }
@@ -268,19 +345,17 @@ pub fn canonicalize(path: &str) -> Option<String> {
 
 pub fn file_path<'a, W: CountedWrite<'a>>(
     writer: &mut W,
-    color: Color,
+    style: Style,
     f: impl FnOnce(&mut W) -> io::Result<()>,
 ) -> io::Result<()> {
-    writer
-        .stdout()
-        .queue(SetForegroundColor(color))?
-        .queue(SetAttribute(Attribute::Underlined))?;
+    set_style(writer.stdout(), style)?;
+    writer.stdout().queue(SetAttribute(Attribute::Underlined))?;
 
     f(writer)?;
 
+    unset_style(writer.stdout(), style)?;
     writer
         .stdout()
-        .queue(SetForegroundColor(Color::Reset))?
         .queue(SetAttribute(Attribute::NoUnderline))?;
 
     Ok(())
@@ -311,3 +386,87 @@ pub fn write_ansi(output: &mut Vec<u8>, command: impl Command) {
 
     let _ = command.write_ansi(&mut FmtWriter(output));
 }
+
+/// Convert captured command output to a string without ANSI escape sequences (colors, links).
+pub fn strip_ansi(output: &[u8]) -> String {
+    let mut stripped = Vec::with_capacity(output.len());
+    let mut iter = output.iter().copied();
+
+    while let Some(c) = iter.next() {
+        if c != b'\x1b' {
+            stripped.push(c);
+            continue;
+        }
+
+        match iter.next() {
+            // CSI: Ends with a byte in the range `@` to `~`.
+            Some(b'[') => {
+                for c in iter.by_ref() {
+                    if (b'@'..=b'~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: Ends with BEL or ST (`ESC \`).
+            Some(b']') => {
+                while let Some(c) = iter.next() {
+                    if c == b'\x07' || (c == b'\x1b' && iter.next() == Some(b'\\')) {
+                        break;
+                    }
+                }
+            }
+            // Character set designations like `ESC ( B` consist of two more bytes.
+            Some(b'(' | b')' | b'*' | b'+') => {
+                iter.next();
+            }
+            // Other escape sequences consist of only one more byte.
+            _ => (),
+        }
+    }
+
+    String::from_utf8_lossy(&stripped).into_owned()
+}
+
+/// Erase output which was printed while streaming it from the terminal.
+/// Lines which were already scrolled out of the terminal can't be erased.
+pub fn erase_streamed(stdout: &mut StdoutLock, streamed: &[u8], term_width: u16) -> io::Result<()> {
+    // Some pseudo terminals report a width of 0.
+    let term_width = match term_width {
+        0 => usize::MAX,
+        width => usize::from(width),
+    };
+    // The cursor is on the last row of the streamed output.
+    let n_rows_above = strip_ansi(streamed)
+        .split('\n')
+        .map(|line| line.chars().count().div_ceil(term_width).max(1))
+        .sum::<usize>()
+        - 1;
+
+    stdout.queue(MoveToColumn(0))?;
+    if n_rows_above > 0 {
+        stdout.queue(MoveToPreviousLine(
+            u16::try_from(n_rows_above).unwrap_or(u16::MAX),
+        ))?;
+    }
+    stdout.queue(Clear(ClearType::FromCursorDown))?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi(b"plain\n"), "plain\n");
+        assert_eq!(
+            strip_ansi(b"\x1b[1m\x1b[31merror\x1b[0m: mismatched types"),
+            "error: mismatched types",
+        );
+        assert_eq!(strip_ansi(b"FAILED\x1b(B\x1b[m."), "FAILED.");
+        assert_eq!(
+            strip_ansi(b"\x1b]8;;file:///tmp/a.rs\x1b\\a.rs\x1b]8;;\x1b\\ done"),
+            "a.rs done",
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use clap::ValueEnum;
+use crossterm::{
+    QueueableCommand,
+    style::{Attribute, Color, SetAttribute, SetForegroundColor},
+};
+use serde::Deserialize;
+use std::{
+    env,
+    io::{self, Write},
+    sync::atomic::{AtomicU8, Ordering::Relaxed},
+};
+
+use crate::term::write_ansi;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[repr(u8)]
+pub enum Theme {
+    Default,
+    /// Bold colors which are readable on light and dark backgrounds and distinguishable with
+    /// color blindness (blue instead of green).
+    HighContrast,
+    /// No colors. Only attributes like bold and symbols.
+    Mono,
+}
+
+static THEME: AtomicU8 = AtomicU8::new(Theme::Default as u8);
+
+/// Set the theme used by all styled output. Without a theme, `Mono` is used if the environment
+/// variable `NO_COLOR` is set to a non-empty value.
+pub fn init(theme: Option<Theme>) {
+    let theme = theme.unwrap_or_else(|| {
+        if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            Theme::Mono
+        } else {
+            Theme::Default
+        }
+    });
+    THEME.store(theme as u8, Relaxed);
+}
+
+pub fn theme() -> Theme {
+    match THEME.load(Relaxed) {
+        1 => Theme::HighContrast,
+        2 => Theme::Mono,
+        _ => Theme::Default,
+    }
+}
+
+/// The value of `--color` for Cargo and test binaries.
+pub fn cargo_color() -> &'static str {
+    if theme() == Theme::Mono {
+        "never"
+    } else {
+        "always"
+    }
+}
+
+/// The role of styled text. Its appearance depends on the theme.
+#[derive(Clone, Copy)]
+pub enum Style {
+    /// Done exercises, successful stages and added lines.
+    Success,
+    /// Failed stages, errors and removed lines.
+    Failure,
+    /// Pending exercises in the list.
+    Pending,
+    Warning,
+    /// Headings, notes and solution links.
+    Info,
+    /// Paths of exercises and source locations.
+    Path,
+    /// Search matches and messages.
+    Highlight,
+    /// Exercises being checked.
+    Active,
+    /// Locked exercises.
+    Muted,
+    Skipped,
+    /// The marker of the current exercise in the list.
+    Current,
+    /// Keys in the list footer.
+    Hotkey,
+}
+
+impl Style {
+    // The color and the attribute of the style in a theme.
+    fn appearance(self, theme: Theme) -> (Option<Color>, Option<Attribute>) {
+        match theme {
+            Theme::Default => {
+                let color = match self {
+                    Self::Success => Color::Green,
+                    Self::Failure | Self::Current => Color::Red,
+                    Self::Pending | Self::Warning | Self::Hotkey => Color::Yellow,
+                    Self::Info => Color::Cyan,
+                    Self::Path | Self::Active | Self::Skipped => Color::Blue,
+                    Self::Highlight => Color::Magenta,
+                    Self::Muted => Color::DarkGrey,
+                };
+                (Some(color), None)
+            }
+            Theme::HighContrast => {
+                let color = match self {
+                    Self::Success | Self::Path => Some(Color::Blue),
+                    Self::Failure | Self::Current => Some(Color::Red),
+                    Self::Pending | Self::Warning | Self::Highlight => Some(Color::Magenta),
+                    Self::Info | Self::Active | Self::Skipped | Self::Hotkey => None,
+                    Self::Muted => return (None, None),
+                };
+                (color, Some(Attribute::Bold))
+            }
+            Theme::Mono => {
+                let attribute = match self {
+                    Self::Success | Self::Pending | Self::Path => None,
+                    Self::Failure | Self::Warning | Self::Info | Self::Current | Self::Hotkey => {
+                        Some(Attribute::Bold)
+                    }
+                    Self::Highlight => Some(Attribute::Underlined),
+                    Self::Active => Some(Attribute::Reverse),
+                    Self::Muted => Some(Attribute::Dim),
+                    Self::Skipped => Some(Attribute::Italic),
+                };
+                (None, attribute)
+            }
+        }
+    }
+}
+
+fn undo_attribute(attribute: Attribute) -> Attribute {
+    match attribute {
+        Attribute::Bold | Attribute::Dim => Attribute::NormalIntensity,
+        Attribute::Italic => Attribute::NoItalic,
+        Attribute::Underlined => Attribute::NoUnderline,
+        Attribute::Reverse => Attribute::NoReverse,
+        _ => Attribute::Reset,
+    }
+}
+
+/// Start writing text in the style.
+pub fn set_style(writer: &mut impl Write, style: Style) -> io::Result<()> {
+    let (color, attribute) = style.appearance(theme());
+    if let Some(color) = color {
+        writer.queue(SetForegroundColor(color))?;
+    }
+    if let Some(attribute) = attribute {
+        writer.queue(SetAttribute(attribute))?;
+    }
+
+    Ok(())
+}
+
+/// Stop writing text in the style while keeping other attributes (unlike `ResetColor`).
+pub fn unset_style(writer: &mut impl Write, style: Style) -> io::Result<()> {
+    let (color, attribute) = style.appearance(theme());
+    if color.is_some() {
+        writer.queue(SetForegroundColor(Color::Reset))?;
+    }
+    if let Some(attribute) = attribute {
+        writer.queue(SetAttribute(undo_attribute(attribute)))?;
+    }
+
+    Ok(())
+}
+
+/// Like `set_style` for output buffers which always contain ANSI escape sequences.
+pub fn set_style_ansi(output: &mut Vec<u8>, style: Style) {
+    let (color, attribute) = style.appearance(theme());
+    if let Some(color) = color {
+        write_ansi(output, SetForegroundColor(color));
+    }
+    if let Some(attribute) = attribute {
+        write_ansi(output, SetAttribute(attribute));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_STYLES: [Style; 12] = [
+        Style::Success,
+        Style::Failure,
+        Style::Pending,
+        Style::Warning,
+        Style::Info,
+        Style::Path,
+        Style::Highlight,
+        Style::Active,
+        Style::Muted,
+        Style::Skipped,
+        Style::Current,
+        Style::Hotkey,
+    ];
+
+    #[test]
+    fn mono_without_colors() {
+        for style in ALL_STYLES {
+            assert_eq!(style.appearance(Theme::Mono).0, None);
+        }
+    }
+
+    #[test]
+    fn distinguishable_stages() {
+        for theme in [Theme::Default, Theme::HighContrast, Theme::Mono] {
+            assert_ne!(
+                Style::Success.appearance(theme),
+                Style::Failure.appearance(theme),
+            );
+        }
+        // No green which is hard to distinguish from red with color blindness.
+        for style in ALL_STYLES {
+            assert_ne!(style.appearance(Theme::HighContrast).0, Some(Color::Green),);
+        }
+    }
+}
@@ -12,8 +12,10 @@ use std::{
 
 use crate::{
     app_state::{AppState, ExercisesProgress},
+    cmd,
+    keymap::{Keymap, WatchAction},
     list,
-    watch::{notify_event::NotifyEventHandler, state::WatchState, terminal_event::InputEvent},
+    watch::{notify_event::NotifyEventHandler, state::WatchState},
 };
 
 mod notify_event;
@@ -27,7 +29,9 @@ static EXERCISE_RUNNING: AtomicBool = AtomicBool::new(false);
 pub struct InputPauseGuard(());
 
 impl InputPauseGuard {
+    /// Only cancelling the running commands is possible while paused.
     pub fn scoped_pause() -> Self {
+        cmd::reset_cancel();
         EXERCISE_RUNNING.store(true, Relaxed);
         Self(())
     }
@@ -40,7 +44,7 @@ impl Drop for InputPauseGuard {
 }
 
 enum WatchEvent {
-    Input(InputEvent),
+    Input(WatchAction),
     FileChange { exercise_ind: usize },
     TerminalResize { width: u16 },
     NotifyErr(notify::Error),
@@ -59,6 +63,7 @@ enum WatchExit {
 fn run_watch(
     app_state: &mut AppState,
     notify_exercise_names: Option<&'static [&'static [u8]]>,
+    keymap: &'static Keymap,
 ) -> Result<WatchExit> {
     let (watch_event_sender, watch_event_receiver) = channel();
 
@@ -87,30 +92,49 @@ fn run_watch(
         None
     };
 
-    let mut watch_state = WatchState::build(app_state, watch_event_sender, manual_run)?;
+    let mut watch_state = WatchState::build(app_state, watch_event_sender, manual_run, keymap)?;
     let mut stdout = io::stdout().lock();
 
     watch_state.run_current_exercise(&mut stdout)?;
 
     while let Ok(event) = watch_event_receiver.recv() {
+        // Any other key closes the help.
+        if let WatchEvent::Input(action) = event
+            && action != WatchAction::Help
+        {
+            watch_state.hide_help();
+        }
+
         match event {
-            WatchEvent::Input(InputEvent::Next) => match watch_state.next_exercise(&mut stdout)? {
+            WatchEvent::Input(WatchAction::Next) => match watch_state.next_exercise(&mut stdout)? {
                 ExercisesProgress::AllDone => break,
                 ExercisesProgress::NewPending => watch_state.run_current_exercise(&mut stdout)?,
-                ExercisesProgress::CurrentPending => (),
+                // Also replaces the progress of a cancelled check of all exercises.
+                ExercisesProgress::CurrentPending => watch_state.render(&mut stdout)?,
             },
-            WatchEvent::Input(InputEvent::Run) => watch_state.run_current_exercise(&mut stdout)?,
-            WatchEvent::Input(InputEvent::Hint) => watch_state.show_hint(&mut stdout)?,
-            WatchEvent::Input(InputEvent::List) => return Ok(WatchExit::List),
-            WatchEvent::Input(InputEvent::CheckAll) => match watch_state
+            WatchEvent::Input(WatchAction::Skip) => {
+                if watch_state.skip_exercise()? {
+                    watch_state.run_current_exercise(&mut stdout)?;
+                } else {
+                    watch_state.render(&mut stdout)?;
+                }
+            }
+            WatchEvent::Input(WatchAction::Run) => watch_state.run_current_exercise(&mut stdout)?,
+            WatchEvent::Input(WatchAction::Hint) => watch_state.show_hint(&mut stdout)?,
+            WatchEvent::Input(WatchAction::Diff) => watch_state.toggle_diff(&mut stdout)?,
+            WatchEvent::Input(WatchAction::Notes) => watch_state.open_notes(&mut stdout)?,
+            WatchEvent::Input(WatchAction::Help) => watch_state.toggle_help(&mut stdout)?,
+            WatchEvent::Input(WatchAction::List) => return Ok(WatchExit::List),
+            WatchEvent::Input(WatchAction::CheckAll) => match watch_state
                 .check_all_exercises(&mut stdout)?
             {
                 ExercisesProgress::AllDone => break,
                 ExercisesProgress::NewPending => watch_state.run_current_exercise(&mut stdout)?,
                 ExercisesProgress::CurrentPending => watch_state.render(&mut stdout)?,
             },
-            WatchEvent::Input(InputEvent::Reset) => watch_state.reset_exercise(&mut stdout)?,
-            WatchEvent::Input(InputEvent::Quit) => {
+            WatchEvent::Input(WatchAction::Reset) => watch_state.reset_exercise(&mut stdout)?,
+            WatchEvent::Input(WatchAction::Quit) => {
+                stdout.write_all(keymap.watch.primary_label(WatchAction::Quit).as_bytes())?;
                 stdout.write_all(QUIT_MSG)?;
                 break;
             }
@@ -133,14 +157,15 @@ fn run_watch(
 fn watch_list_loop(
     app_state: &mut AppState,
     notify_exercise_names: Option<&'static [&'static [u8]]>,
+    keymap: &'static Keymap,
 ) -> Result<()> {
     loop {
-        match run_watch(app_state, notify_exercise_names)? {
+        match run_watch(app_state, notify_exercise_names, keymap)? {
             WatchExit::Shutdown => break Ok(()),
             // It is much easier to exit the watch mode, launch the list mode and then restart
             // the watch mode instead of trying to pause the watch threads and correct the
             // watch state.
-            WatchExit::List => list::list(app_state)?,
+            WatchExit::List => list::list(app_state, &keymap.list)?,
         }
     }
 }
@@ -149,6 +174,7 @@ fn watch_list_loop(
 pub fn watch(
     app_state: &mut AppState,
     notify_exercise_names: Option<&'static [&'static [u8]]>,
+    keymap: &'static Keymap,
 ) -> Result<()> {
     // TODO: Use cfg_select! after bumping MSRV to at least 1.95
     #[cfg(not(windows))]
@@ -161,7 +187,7 @@ pub fn watch(
             rustix::termios::LocalModes::ICANON | rustix::termios::LocalModes::ECHO;
         rustix::termios::tcsetattr(stdin_fd, rustix::termios::OptionalActions::Now, &termios)?;
 
-        let res = watch_list_loop(app_state, notify_exercise_names);
+        let res = watch_list_loop(app_state, notify_exercise_names, keymap);
 
         termios.local_modes = original_local_modes;
         rustix::termios::tcsetattr(stdin_fd, rustix::termios::OptionalActions::Now, &termios)?;
@@ -170,10 +196,10 @@ pub fn watch(
     }
 
     #[cfg(windows)]
-    watch_list_loop(app_state, notify_exercise_names)
+    watch_list_loop(app_state, notify_exercise_names, keymap)
 }
 
-const QUIT_MSG: &[u8] = b"q\n
+const QUIT_MSG: &[u8] = b"\n
 We hope you're enjoying learning Rust!
 If you want to continue working on the exercises at a later point, you can simply run `rustlings` again in this directory.
 ";
@@ -1,22 +1,26 @@
 use anyhow::{Context, Result};
 use crossterm::{
     QueueableCommand,
-    style::{
-        Attribute, Attributes, Color, ResetColor, SetAttribute, SetAttributes, SetForegroundColor,
-    },
+    style::{Attribute, Attributes, ResetColor, SetAttribute, SetAttributes},
     terminal,
 };
 use std::{
     io::{self, Read, StdoutLock, Write},
+    path::Path,
     sync::mpsc::{Sender, SyncSender, sync_channel},
     thread,
 };
 
 use crate::{
-    app_state::{AppState, ExercisesProgress},
+    app_state::{AppState, CheckAllOutcome, ExercisesProgress},
     clear_terminal,
-    exercise::{OUTPUT_CAPACITY, RunnableExercise, solution_link_line},
-    term::progress_bar,
+    diagnostics::{Diagnostic, render_diagnostics},
+    diff,
+    exercise::{OUTPUT_CAPACITY, RunnableExercise, StageOutcome, solution_link_line},
+    keymap::{Keymap, WatchAction},
+    notes,
+    term::{CountedWrite, canonicalize, file_path, progress_bar, terminal_file_link},
+    theme::{Style, set_style},
     watch::{InputPauseGuard, WatchEvent, terminal_event::terminal_event_handler},
 };
 
@@ -34,9 +38,19 @@ enum DoneStatus {
 pub struct WatchState<'a> {
     app_state: &'a mut AppState,
     output: Vec<u8>,
-    show_hint: bool,
+    // The outcomes of the stages of the last run.
+    stages: Vec<StageOutcome>,
+    // The compiler diagnostics of the last run.
+    diagnostics: Vec<Diagnostic>,
+    // The number of shown hint levels. `0` if the hint is hidden.
+    hint_level: u32,
+    // The rendered diff between the exercise and its solution. Empty if hidden.
+    diff: Vec<u8>,
     done_status: DoneStatus,
     manual_run: bool,
+    keymap: &'static Keymap,
+    // Whether the help with the key bindings is shown instead of the output.
+    show_help: bool,
     term_width: u16,
     terminal_event_unpause_sender: SyncSender<()>,
 }
@@ -46,6 +60,7 @@ impl<'a> WatchState<'a> {
         app_state: &'a mut AppState,
         watch_event_sender: Sender<WatchEvent>,
         manual_run: bool,
+        keymap: &'static Keymap,
     ) -> Result<Self> {
         let term_width = terminal::size()
             .context("Failed to get the terminal size")?
@@ -59,6 +74,7 @@ impl<'a> WatchState<'a> {
                     watch_event_sender,
                     terminal_event_unpause_receiver,
                     manual_run,
+                    &keymap.watch,
                 );
             })
             .context("Failed to spawn a thread to handle terminal events")?;
@@ -66,34 +82,48 @@ impl<'a> WatchState<'a> {
         Ok(Self {
             app_state,
             output: Vec::with_capacity(OUTPUT_CAPACITY),
-            show_hint: false,
+            stages: Vec::new(),
+            diagnostics: Vec::new(),
+            hint_level: 0,
+            diff: Vec::new(),
             done_status: DoneStatus::Pending,
             manual_run,
+            keymap,
+            show_help: false,
             term_width,
             terminal_event_unpause_sender,
         })
     }
 
     pub fn run_current_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
-        // Ignore any input until running the exercise is done.
+        // Ignore any input except for cancelling until running the exercise is done.
         let _input_pause_guard = InputPauseGuard::scoped_pause();
 
         writeln!(
             stdout,
-            "\nChecking the exercise `{}`. Please wait…",
+            "\nChecking the exercise `{}`. Please wait… ({}/esc to cancel)",
             self.app_state.current_exercise().name,
+            self.keymap.watch.primary_label(WatchAction::Quit),
         )?;
 
         let editor_handle = self.app_state.open_editor()?;
 
-        self.show_hint = false;
+        self.hint_level = 0;
+        self.diff.clear();
 
-        let success = self
+        let outcome = self
             .app_state
             .current_exercise()
             .run_exercise(Some(&mut self.output), self.app_state.cmd_runner())?;
+        let success = outcome.success();
+        let cancelled = outcome.cancelled();
+        self.stages = outcome.stages;
+        self.diagnostics = outcome.diagnostics;
         self.output.push(b'\n');
-        if success {
+        if cancelled {
+            // Keep the exercise's state, but require a new run to move on.
+            self.done_status = DoneStatus::Pending;
+        } else if success {
             self.done_status =
                 if let Some(solution_path) = self.app_state.current_solution_path()? {
                     DoneStatus::DoneWithSolution(solution_path)
@@ -107,6 +137,10 @@ impl<'a> WatchState<'a> {
             self.done_status = DoneStatus::Pending;
         }
 
+        if !cancelled {
+            self.app_state.record_run()?;
+        }
+
         self.app_state.join_editor_handle(editor_handle)?;
         self.render(stdout)?;
 
@@ -116,9 +150,30 @@ impl<'a> WatchState<'a> {
     pub fn reset_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
         clear_terminal(stdout)?;
 
-        stdout.write_all(b"Resetting will undo all your changes to the file ")?;
-        stdout.write_all(self.app_state.current_exercise().path.as_bytes())?;
-        stdout.write_all(b"\nReset (y/n)? ")?;
+        let current_exercise_ind = self.app_state.current_exercise_ind();
+        let path = self.app_state.current_exercise().path;
+        match self
+            .app_state
+            .write_exercise_changes(current_exercise_ind, stdout)
+        {
+            Ok(true) => {
+                write!(
+                    stdout,
+                    "\nResetting will undo these changes to the file {path}\n\
+                     You can restore them with `rustlings reset --undo {}`\n",
+                    self.app_state.current_exercise().name,
+                )?;
+            }
+            Ok(false) => writeln!(stdout, "You didn't change the file {path}")?,
+            Err(e) => {
+                writeln!(
+                    stdout,
+                    "Failed to show your changes: {e:#}\n\n\
+                     Resetting will undo all your changes to the file {path}",
+                )?;
+            }
+        }
+        stdout.write_all(b"Reset (y/n)? ")?;
         stdout.flush()?;
 
         {
@@ -171,67 +226,137 @@ impl<'a> WatchState<'a> {
             DoneStatus::Pending => return Ok(ExercisesProgress::CurrentPending),
         }
 
-        self.app_state.done_current_exercise::<true>(stdout)
+        // Checking all exercises after the last one can be cancelled.
+        let _input_pause_guard = InputPauseGuard::scoped_pause();
+        self.app_state.done_current_exercise::<true>(Some(stdout))
     }
 
-    fn show_prompt(&self, stdout: &mut StdoutLock) -> io::Result<()> {
+    // Skip the current exercise if it is pending.
+    // Returns `true` if another exercise became the current one.
+    pub fn skip_exercise(&mut self) -> Result<bool> {
         if self.done_status != DoneStatus::Pending {
+            return Ok(false);
+        }
+
+        self.app_state.skip_current_exercise()
+    }
+
+    fn show_prompt(&self, stdout: &mut StdoutLock) -> io::Result<()> {
+        let show_key = |stdout: &mut StdoutLock, action, postfix: &[u8]| {
             stdout.queue(SetAttribute(Attribute::Bold))?;
-            stdout.write_all(b"n")?;
+            stdout.write_all(self.keymap.watch.primary_label(action).as_bytes())?;
             stdout.queue(ResetColor)?;
-            stdout.write_all(b":")?;
+            stdout.write_all(postfix)
+        };
+
+        if self.done_status != DoneStatus::Pending {
+            show_key(stdout, WatchAction::Next, b":")?;
             stdout.queue(SetAttribute(Attribute::Underlined))?;
             stdout.write_all(b"next")?;
             stdout.queue(ResetColor)?;
             stdout.write_all(b" / ")?;
         }
 
-        let mut show_key = |key, postfix| {
-            stdout.queue(SetAttribute(Attribute::Bold))?;
-            stdout.write_all(&[key])?;
-            stdout.queue(ResetColor)?;
-            stdout.write_all(postfix)
-        };
-
         if self.manual_run {
-            show_key(b'r', b":run / ")?;
+            show_key(stdout, WatchAction::Run, b":run / ")?;
+        }
+
+        if self.done_status == DoneStatus::Pending {
+            show_key(stdout, WatchAction::Skip, b":skip / ")?;
         }
 
-        if !self.show_hint {
-            show_key(b'h', b":hint / ")?;
+        if let DoneStatus::DoneWithSolution(_) = self.done_status {
+            if self.diff.is_empty() {
+                show_key(stdout, WatchAction::Diff, b":diff / ")?;
+            } else {
+                show_key(stdout, WatchAction::Diff, b":hide diff / ")?;
+            }
         }
 
-        show_key(b'l', b":list / ")?;
-        show_key(b'c', b":check all / ")?;
-        show_key(b'x', b":reset / ")?;
-        show_key(b'q', b":quit ? ")?;
+        if self.hint_level == 0 {
+            show_key(stdout, WatchAction::Hint, b":hint / ")?;
+        } else if self.hint_level < self.app_state.current_exercise().n_hint_levels() {
+            show_key(stdout, WatchAction::Hint, b":next hint / ")?;
+        }
+
+        show_key(stdout, WatchAction::Notes, b":notes / ")?;
+        show_key(stdout, WatchAction::List, b":list / ")?;
+        show_key(stdout, WatchAction::CheckAll, b":check all / ")?;
+        show_key(stdout, WatchAction::Reset, b":reset / ")?;
+        if self.show_help {
+            show_key(stdout, WatchAction::Help, b":hide help / ")?;
+        } else {
+            show_key(stdout, WatchAction::Help, b":help / ")?;
+        }
+        show_key(stdout, WatchAction::Quit, b":quit ? ")?;
 
         stdout.flush()
     }
 
+    // A compact line like `build ✓  test ✗  run ✓`.
+    fn render_stage_summary(&self, stdout: &mut StdoutLock) -> io::Result<()> {
+        if self.stages.is_empty() {
+            return Ok(());
+        }
+
+        for (ind, outcome) in self.stages.iter().enumerate() {
+            if ind > 0 {
+                stdout.write_all(b"  ")?;
+            }
+
+            stdout.write_all(outcome.stage.name().as_bytes())?;
+            stdout.write_all(b" ")?;
+            if outcome.success {
+                set_style(stdout, Style::Success)?;
+                stdout.write_all("✓".as_bytes())?;
+            } else {
+                set_style(stdout, Style::Failure)?;
+                stdout.write_all("✗".as_bytes())?;
+                if outcome.timed_out {
+                    stdout.write_all(b" (timed out)")?;
+                } else if outcome.cancelled {
+                    stdout.write_all(b" (cancelled)")?;
+                }
+            }
+            stdout.queue(ResetColor)?;
+        }
+
+        stdout.write_all(b"\n\n")
+    }
+
     pub fn render(&self, stdout: &mut StdoutLock) -> io::Result<()> {
         // Prevent having the first line shifted if clearing wasn't successful.
         stdout.write_all(b"\n")?;
         clear_terminal(stdout)?;
 
+        if self.show_help {
+            return self.render_help(stdout);
+        }
+
+        self.render_stage_summary(stdout)?;
+        render_diagnostics(stdout, &self.diagnostics, self.app_state.emit_file_links())?;
         stdout.write_all(&self.output)?;
 
-        if self.show_hint {
-            stdout
-                .queue(SetAttributes(HEADING_ATTRIBUTES))?
-                .queue(SetForegroundColor(Color::Cyan))?;
-            stdout.write_all(b"Hint")?;
+        let current_exercise = self.app_state.current_exercise();
+        for (level, hint) in (1..=self.hint_level).zip(&current_exercise.hints) {
+            stdout.queue(SetAttributes(HEADING_ATTRIBUTES))?;
+            set_style(stdout, Style::Info)?;
+            current_exercise.write_hint_heading(stdout, level)?;
             stdout.queue(ResetColor)?;
             stdout.write_all(b"\n")?;
 
-            stdout.write_all(self.app_state.current_exercise().hint.as_bytes())?;
+            stdout.write_all(hint.as_bytes())?;
             stdout.write_all(b"\n\n")?;
         }
 
+        if !self.diff.is_empty() {
+            stdout.write_all(&self.diff)?;
+            stdout.write_all(b"\n")?;
+        }
+
         if self.done_status != DoneStatus::Pending {
-            stdout
-                .queue(SetAttribute(Attribute::Bold))?
-                .queue(SetForegroundColor(Color::Green))?;
+            stdout.queue(SetAttribute(Attribute::Bold))?;
+            set_style(stdout, Style::Success)?;
             stdout.write_all("Exercise done ✓".as_bytes())?;
             stdout.queue(ResetColor)?;
             stdout.write_all(b"\n")?;
@@ -240,23 +365,45 @@ impl<'a> WatchState<'a> {
                 solution_link_line(stdout, solution_path, self.app_state.emit_file_links())?;
             }
 
-            stdout.write_all(
-                "When done experimenting, enter `n` to move on to the next exercise 🦀\n\n"
-                    .as_bytes(),
+            write!(
+                stdout,
+                "When done experimenting, enter `{}` to move on to the next exercise 🦀\n\n",
+                self.keymap.watch.primary_label(WatchAction::Next),
             )?;
         }
 
+        self.render_footer(stdout)
+    }
+
+    // The progress bar, the current exercise and the prompt.
+    fn render_footer(&self, stdout: &mut StdoutLock) -> io::Result<()> {
         progress_bar(
             stdout,
             self.app_state.n_done(),
-            self.app_state.exercises().len() as u32,
+            self.app_state.n_exercises(),
             self.term_width,
         )?;
 
         stdout.write_all(b"\nCurrent exercise: ")?;
-        self.app_state
-            .current_exercise()
-            .terminal_file_link(stdout, self.app_state.emit_file_links())?;
+        let current_exercise = self.app_state.current_exercise();
+        current_exercise.terminal_file_link(stdout, self.app_state.emit_file_links())?;
+        if current_exercise.skipped {
+            stdout.write_all(b" (skipped before)")?;
+        }
+
+        let notes_path = notes::notes_path(current_exercise.name);
+        if Path::new(&notes_path).exists() {
+            stdout.write_all(b"\nYour notes: ")?;
+            file_path(stdout, Style::Path, |writer| {
+                if self.app_state.emit_file_links()
+                    && let Some(canonical_path) = canonicalize(&notes_path)
+                {
+                    terminal_file_link(writer, &notes_path, &canonical_path)
+                } else {
+                    writer.write_str(&notes_path)
+                }
+            })?;
+        }
         stdout.write_all(b"\n\n")?;
 
         self.show_prompt(stdout)?;
@@ -264,31 +411,101 @@ impl<'a> WatchState<'a> {
         Ok(())
     }
 
-    pub fn show_hint(&mut self, stdout: &mut StdoutLock) -> io::Result<()> {
-        if !self.show_hint {
-            self.show_hint = true;
-            self.render(stdout)?;
+    // Show the levels revealed before (at least the first one) or reveal the next level.
+    pub fn show_hint(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let current_exercise = self.app_state.current_exercise();
+        let n_levels = current_exercise.n_hint_levels();
+        if self.hint_level == n_levels {
+            return Ok(());
         }
 
+        self.hint_level = if self.hint_level == 0 {
+            current_exercise.history.hint_level.clamp(1, n_levels)
+        } else {
+            self.hint_level + 1
+        };
+        self.app_state.reveal_hint(self.hint_level)?;
+
+        self.render(stdout)?;
+
+        Ok(())
+    }
+
+    fn render_help(&self, stdout: &mut StdoutLock) -> io::Result<()> {
+        stdout.queue(SetAttributes(HEADING_ATTRIBUTES))?;
+        set_style(stdout, Style::Info)?;
+        stdout.write_all(b"Key bindings")?;
+        stdout.queue(ResetColor)?;
+        stdout.write_all(b"\n")?;
+
+        for line in self.keymap.watch.help_lines() {
+            stdout.write_all(line.as_bytes())?;
+            stdout.write_all(b"\n")?;
+        }
+        stdout.write_all(b"\n")?;
+
+        self.render_footer(stdout)
+    }
+
+    pub fn toggle_help(&mut self, stdout: &mut StdoutLock) -> io::Result<()> {
+        self.show_help = !self.show_help;
+        self.render(stdout)
+    }
+
+    pub fn hide_help(&mut self) {
+        self.show_help = false;
+    }
+
+    // Open the notes of the current exercise in the editor.
+    // Without an editor, the notes file is only created and its path is shown.
+    pub fn open_notes(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        self.app_state.open_notes()?;
+        self.render(stdout)?;
+
+        Ok(())
+    }
+
+    // Show or hide the diff between the done exercise and its solution.
+    pub fn toggle_diff(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let DoneStatus::DoneWithSolution(solution_path) = &self.done_status else {
+            return Ok(());
+        };
+
+        if self.diff.is_empty() {
+            diff::diff_with_solution(
+                &mut self.diff,
+                self.app_state.current_exercise().path,
+                solution_path,
+            )?;
+        } else {
+            self.diff.clear();
+        }
+
+        self.render(stdout)?;
+
         Ok(())
     }
 
     pub fn check_all_exercises(&mut self, stdout: &mut StdoutLock) -> Result<ExercisesProgress> {
-        // Ignore any input until checking all exercises is done.
+        // Ignore any input except for cancelling until checking all exercises is done.
         let _input_pause_guard = InputPauseGuard::scoped_pause();
 
-        if let Some(first_pending_exercise_ind) = self.app_state.check_all_exercises(stdout)? {
+        match self.app_state.check_all_exercises(stdout)? {
+            CheckAllOutcome::AllDone => {
+                self.app_state.render_final_message(stdout)?;
+                Ok(ExercisesProgress::AllDone)
+            }
             // Only change exercise if the current one is done.
-            if self.app_state.current_exercise().done {
+            CheckAllOutcome::Pending(first_pending_exercise_ind)
+                if self.app_state.current_exercise().done =>
+            {
                 self.app_state
                     .set_current_exercise_ind(first_pending_exercise_ind)?;
                 Ok(ExercisesProgress::NewPending)
-            } else {
+            }
+            CheckAllOutcome::Pending(_) | CheckAllOutcome::Cancelled => {
                 Ok(ExercisesProgress::CurrentPending)
             }
-        } else {
-            self.app_state.render_final_message(stdout)?;
-            Ok(ExercisesProgress::AllDone)
         }
     }
 
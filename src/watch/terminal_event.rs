@@ -4,22 +4,17 @@ use std::sync::{
     mpsc::{Receiver, Sender},
 };
 
-use crate::watch::{EXERCISE_RUNNING, WatchEvent};
-
-pub enum InputEvent {
-    Next,
-    Run,
-    Hint,
-    List,
-    CheckAll,
-    Reset,
-    Quit,
-}
+use crate::{
+    cmd,
+    keymap::{Bindings, WatchAction},
+    watch::{EXERCISE_RUNNING, WatchEvent},
+};
 
 pub fn terminal_event_handler(
     sender: Sender<WatchEvent>,
     unpause_receiver: Receiver<()>,
     manual_run: bool,
+    bindings: &Bindings<WatchAction>,
 ) {
     let last_watch_event = loop {
         match event::read() {
@@ -29,18 +24,21 @@ pub fn terminal_event_handler(
                     KeyEventKind::Press => (),
                 }
 
+                let action = bindings.action(key.code);
+
                 if EXERCISE_RUNNING.load(Relaxed) {
+                    if key.code == KeyCode::Esc || action == Some(WatchAction::Quit) {
+                        cmd::cancel();
+                    }
+
                     continue;
                 }
 
-                let input_event = match key.code {
-                    KeyCode::Char('n') => InputEvent::Next,
-                    KeyCode::Char('r') if manual_run => InputEvent::Run,
-                    KeyCode::Char('h') => InputEvent::Hint,
-                    KeyCode::Char('l') => break WatchEvent::Input(InputEvent::List),
-                    KeyCode::Char('c') => InputEvent::CheckAll,
-                    KeyCode::Char('x') => {
-                        if sender.send(WatchEvent::Input(InputEvent::Reset)).is_err() {
+                let input_event = match action {
+                    Some(WatchAction::Run) if !manual_run => continue,
+                    Some(WatchAction::List) => break WatchEvent::Input(WatchAction::List),
+                    Some(WatchAction::Reset) => {
+                        if sender.send(WatchEvent::Input(WatchAction::Reset)).is_err() {
                             return;
                         }
 
@@ -51,8 +49,9 @@ pub fn terminal_event_handler(
 
                         continue;
                     }
-                    KeyCode::Char('q') => break WatchEvent::Input(InputEvent::Quit),
-                    _ => continue,
+                    Some(WatchAction::Quit) => break WatchEvent::Input(WatchAction::Quit),
+                    Some(action) => action,
+                    None => continue,
                 };
 
                 if sender.send(WatchEvent::Input(input_event)).is_err() {
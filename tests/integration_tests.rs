@@ -1,8 +1,20 @@
 use std::{
+    fs::{self, OpenOptions},
+    path::Path,
     process::{Command, Stdio},
     str::from_utf8,
+    sync::{Mutex, MutexGuard, PoisonError},
 };
 
+// Rustlings refuses to run while another instance holds the lock in the same directory.
+static TEST_EXERCISES_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_test_exercises() -> MutexGuard<'static, ()> {
+    TEST_EXERCISES_LOCK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
 enum Output<'a> {
     PartialStdout(&'a str),
     PartialStderr(&'a str),
@@ -43,7 +55,10 @@ impl<'a> Cmd<'a> {
 
         cmd.args(self.args).stdin(Stdio::null());
 
-        let output = cmd.output().unwrap();
+        let output = {
+            let _guard = lock_test_exercises();
+            cmd.output().unwrap()
+        };
         match self.output {
             None => (),
             Some(PartialStdout(stdout)) => {
@@ -120,6 +135,55 @@ fn reset_without_exercise_name() {
     Cmd::default().args(&["reset"]).fail();
 }
 
+#[test]
+fn reset_pattern_cancelled() {
+    // Stdin is empty which cancels the confirmation.
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["reset", "test_*"])
+        .output(PartialStdout("exercises/test_failure.rs"))
+        .success();
+
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["reset", "--dir", "nothing"])
+        .output(PartialStderr(
+            "No exercises found in the directory 'nothing'",
+        ))
+        .fail();
+}
+
+#[test]
+fn reset_undo_without_reset() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["reset", "--undo", "test_failure"])
+        .output(PartialStderr(
+            "no changes to the exercise `test_failure` discarded",
+        ))
+        .fail();
+}
+
+#[test]
+fn unknown_track() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["--track", "nope", "hint", "test_failure"])
+        .output(PartialStderr(
+            "There is no track named `nope` in `info.toml`. The available tracks: passing",
+        ))
+        .fail();
+}
+
+#[test]
+fn notes_export() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["notes", "export"])
+        .output(PartialStdout("# Rustlings notes\n"))
+        .success();
+}
+
 #[test]
 fn hint() {
     Cmd::default()
@@ -157,3 +221,212 @@ fn init() {
         .output(PartialStderr("already initialized"))
         .fail();
 }
+
+#[test]
+fn run_test_failure_json() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["run", "test_failure", "--format", "json"])
+        .output(PartialStdout(
+            "\"stage\": \"test\",\n      \"success\": false,\n",
+        ))
+        .fail();
+}
+
+#[test]
+fn hint_json() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["hint", "test_failure", "--format", "json"])
+        .output(PartialStdout(
+            "\"hint\": \"The answer to everything: 42\"\n",
+        ))
+        .success();
+}
+
+#[test]
+fn stats_json() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["stats", "--format", "json"])
+        .output(PartialStdout("\"n_exercises\": 4,\n"))
+        .success();
+}
+
+#[test]
+fn export() {
+    let archive_dir = tempfile::TempDir::new().unwrap();
+    let archive_path = archive_dir.path().join("progress.json");
+    let archive_path = archive_path.to_str().unwrap();
+
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["export", archive_path])
+        .output(PartialStdout("Exported the progress and 0 exercise files"))
+        .success();
+
+    // The exercise files weren't modified since their original copies were recorded.
+    let archive = fs::read_to_string(archive_path).unwrap();
+    assert!(archive.contains("\"files\": []"));
+}
+
+#[test]
+fn locked() {
+    let _guard = lock_test_exercises();
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open("tests/test_exercises/.rustlings-state.txt.lock")
+        .unwrap();
+    lock_file.lock().unwrap();
+
+    let output = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_rustlings"))
+            .current_dir("tests/test_exercises")
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+
+    let run = output(&["run", "compilation_success"]);
+    assert!(!run.status.success());
+    assert!(
+        from_utf8(&run.stderr)
+            .unwrap()
+            .contains("Rustlings is already running in this directory")
+    );
+
+    // Read-only commands don't need the lock.
+    assert!(output(&["hint", "test_failure"]).status.success());
+
+    assert!(
+        output(&["--force", "run", "compilation_success"])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn hint_level() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["hint", "test_success", "--level", "2"])
+        .output(PartialStdout("Hint 2/2:\nThe test already passes.\n"))
+        .success();
+
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["hint", "test_success", "--level", "3"])
+        .output(PartialStderr("hint levels from 1 to 2"))
+        .fail();
+}
+
+#[test]
+fn diff_pending() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["diff", "test_failure"])
+        .output(PartialStderr("isn't done yet"))
+        .fail();
+}
+
+#[test]
+fn check_all_headless() {
+    let report_dir = tempfile::TempDir::new().unwrap();
+    let report_path = report_dir.path().join("report.tap");
+    let report_path = report_path.to_str().unwrap();
+
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&[
+            "check-all",
+            "--headless",
+            "--report",
+            report_path,
+            "--report-format",
+            "tap",
+        ])
+        .output(PartialStdout(
+            "1/4 done    compilation_success\n2/4 pending compilation_failure\n",
+        ))
+        .fail();
+
+    let report = fs::read_to_string(report_path).unwrap();
+    assert!(report.starts_with("TAP version 14\n1..4\nok 1 - compilation_success\n"));
+    assert!(report.contains("not ok 4 - test_failure\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn interrupt_endless_loop() {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    const DIR: &str = "tests/endless_exercises";
+    let pid_path = Path::new(DIR).join("exercise.pid");
+    let _ = fs::remove_file(&pid_path);
+
+    // Without a time limit, only the interruption stops the exercise.
+    let mut rustlings = Command::new(env!("CARGO_BIN_EXE_rustlings"))
+        .current_dir(DIR)
+        .args(["--timeout", "0", "run", "endless_loop"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let start = Instant::now();
+    let exercise_pid = loop {
+        if let Ok(pid) = fs::read_to_string(&pid_path)
+            && !pid.is_empty()
+        {
+            break pid;
+        }
+
+        assert!(start.elapsed() < Duration::from_secs(120));
+        thread::sleep(Duration::from_millis(50));
+    };
+
+    // Like Ctrl+C which only signals the foreground process group of the terminal.
+    let interrupt = |pid: &str| {
+        Command::new("kill")
+            .args(["-INT", pid])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap()
+            .success()
+    };
+    assert!(interrupt(&rustlings.id().to_string()));
+    assert!(!rustlings.wait().unwrap().success());
+
+    let exercise_running = || {
+        Command::new("kill")
+            .args(["-0", &exercise_pid])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap()
+            .success()
+    };
+    let start = Instant::now();
+    while exercise_running() {
+        if start.elapsed() > Duration::from_secs(5) {
+            interrupt(&exercise_pid);
+            panic!("The exercise kept running after interrupting Rustlings");
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    for file in [
+        "exercise.pid",
+        ".rustlings-state.txt",
+        ".rustlings-state.txt.bak",
+        ".rustlings-state.txt.lock",
+    ] {
+        let _ = fs::remove_file(Path::new(DIR).join(file));
+    }
+}